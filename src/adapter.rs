@@ -0,0 +1,104 @@
+use futures::channel::mpsc::UnboundedSender;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, Weak};
+use uuid::Uuid;
+
+pub trait PollAdapter: Send + Sync + 'static {
+    fn on_pending(&self);
+    fn on_complete(&self);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PollEvent {
+    Pending,
+    Complete,
+}
+
+impl PollAdapter for UnboundedSender<PollEvent> {
+    fn on_pending(&self) {
+        let _ = self.unbounded_send(PollEvent::Pending);
+    }
+    fn on_complete(&self) {
+        let _ = self.unbounded_send(PollEvent::Complete);
+    }
+}
+
+impl<F: Fn(PollEvent) + Send + Sync + 'static> PollAdapter for F {
+    fn on_pending(&self) {
+        self(PollEvent::Pending)
+    }
+    fn on_complete(&self) {
+        self(PollEvent::Complete)
+    }
+}
+
+type Attached = (Weak<()>, Vec<Arc<dyn PollAdapter>>);
+
+#[derive(Default)]
+pub(crate) struct Adapters {
+    attached: Mutex<HashMap<Uuid, Attached>>,
+}
+
+impl Adapters {
+    pub(crate) fn attach(&self, id: Uuid, owner: Weak<()>, adapter: Arc<dyn PollAdapter>) {
+        let mut attached = self.lock();
+        attached.retain(|_, (owner, _)| owner.strong_count() > 0);
+        attached
+            .entry(id)
+            .or_insert_with(|| (owner, Vec::new()))
+            .1
+            .push(adapter);
+    }
+    pub(crate) fn detach(&self, id: &Uuid) {
+        self.lock().remove(id);
+    }
+    pub(crate) fn notify(&self, id: &Uuid, event: PollEvent) {
+        let adapters = match self.lock().get(id) {
+            Some((_, adapters)) => adapters.clone(),
+            None => return,
+        };
+        for adapter in adapters {
+            match event {
+                PollEvent::Pending => adapter.on_pending(),
+                PollEvent::Complete => adapter.on_complete(),
+            }
+        }
+    }
+    #[cfg(target_arch = "wasm32")]
+    pub(crate) fn notify_all(&self, event: PollEvent) {
+        let attached = self.lock().keys().copied().collect::<Vec<_>>();
+        attached.iter().for_each(|id| self.notify(id, event));
+    }
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Uuid, Attached>> {
+        self.attached
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::PollEvent;
+    use crate::Perform;
+    use std::sync::{Arc, Mutex};
+
+    mod adapted {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    async fn attached_adapters_hear_pending_and_complete() {
+        let session = adapted::Session::activate().await;
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let recorder = events.clone();
+        session.attach(move |event| recorder.lock().unwrap().push(event));
+        session.perform(async { 1 }).await;
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![PollEvent::Pending, PollEvent::Complete]
+        );
+        session.detach();
+        session.perform(async { 2 }).await;
+        assert_eq!(events.lock().unwrap().len(), 2);
+    }
+}
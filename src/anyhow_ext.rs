@@ -0,0 +1,60 @@
+use crate::{async_trait, Perform, PerformError, Uuid};
+
+pub trait SessionContext<T> {
+    fn with_session(self, id: &Uuid) -> anyhow::Result<T>;
+}
+
+impl<T> SessionContext<T> for Result<T, PerformError> {
+    fn with_session(self, id: &Uuid) -> anyhow::Result<T> {
+        self.map_err(|e| anyhow::Error::new(e).context(format!("session {}", id)))
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait PerformAnyhow<T: Send>: Perform<anyhow::Result<T>> + Sync {
+    fn try_take_anyhow(&self) -> anyhow::Result<T> {
+        self.try_take().with_session(self.id())?
+    }
+    async fn take_anyhow(&self) -> anyhow::Result<T> {
+        self.take().await.with_session(self.id())?
+    }
+}
+
+impl<T: Send, S> PerformAnyhow<T> for S where S: Perform<anyhow::Result<T>> + Sync {}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(not(target_arch = "wasm32"))]
+    use super::PerformAnyhow;
+    use super::SessionContext;
+    #[cfg(not(target_arch = "wasm32"))]
+    use crate::Perform;
+    use crate::PerformError;
+
+    mod fallible {
+        crate::build_perform!(anyhow::Result<u32>);
+    }
+
+    #[test]
+    fn with_session_mentions_id() {
+        let id = crate::Uuid::new_v4();
+        let result: Result<u32, PerformError> = Err(PerformError::Empty);
+        let error = result.with_session(&id).unwrap_err();
+        assert!(format!("{:#}", error).contains(&id.to_string()));
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn take_anyhow_flattens() {
+        let session = fallible::Session::activate().await;
+        session.perform(async { Ok(7) }).await;
+        assert_eq!(session.take_anyhow().await.unwrap(), 7);
+
+        session
+            .perform(async { Err(anyhow::anyhow!("boom")) })
+            .await;
+        assert!(session.take_anyhow().await.is_err());
+        assert!(session.try_take_anyhow().is_err());
+    }
+}
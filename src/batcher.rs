@@ -0,0 +1,201 @@
+use crate::session::Dispatch;
+use crate::{Perform, PerformError, PerformHandle, Session, StoreProvider};
+use futures::future::LocalBoxFuture;
+use futures::FutureExt;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::rc::Rc;
+use std::time::Duration;
+
+type Executor<K, V> =
+    Rc<dyn Fn(Vec<K>) -> LocalBoxFuture<'static, Result<HashMap<K, V>, PerformError>>>;
+type Waiting<K> = Vec<(K, crate::Uuid, crate::InsertOptions)>;
+type Window<K> = Rc<RefCell<Option<Waiting<K>>>>;
+
+pub struct Batcher<K, P: StoreProvider> {
+    window: Duration,
+    executor: Executor<K, P::Value>,
+    pending: Window<K>,
+    provider: PhantomData<P>,
+}
+impl<K, P> Batcher<K, P>
+where
+    K: Eq + Hash + Clone + 'static,
+    P: StoreProvider,
+    P::Value: Clone,
+{
+    pub fn new<F, Fut>(window: Duration, executor: F) -> Self
+    where
+        F: Fn(Vec<K>) -> Fut + 'static,
+        Fut: Future<Output = Result<HashMap<K, P::Value>, PerformError>> + 'static,
+    {
+        Self {
+            window,
+            executor: Rc::new(move |keys| executor(keys).boxed_local()),
+            pending: Default::default(),
+            provider: PhantomData,
+        }
+    }
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+    pub fn pending(&self) -> usize {
+        self.pending.borrow().as_ref().map_or(0, Vec::len)
+    }
+    pub async fn perform(&self, session: &Session<P>, key: K) {
+        if let Some(fut) = self.enqueue(session, key) {
+            session.perform_attempted(fut, Dispatch::default()).await
+        }
+    }
+    pub fn perform_with_spawn_local(&self, session: &Session<P>, key: K) -> Option<PerformHandle> {
+        let fut = self.enqueue(session, key)?;
+        Some(session.spawn_staged_with_spawn_local(|_| fut, Dispatch::default()))
+    }
+    fn enqueue(
+        &self,
+        session: &Session<P>,
+        key: K,
+    ) -> Option<impl Future<Output = (Result<P::Value, PerformError>, u32)> + 'static> {
+        let mut pending = self.pending.borrow_mut();
+        if let Some(waiters) = pending.as_mut() {
+            waiters.push((key, *session.id(), session.next_generation()));
+            return None;
+        }
+        *pending = Some(Vec::new());
+        let window = Flush::<K, P> {
+            pending: Some(self.pending.clone()),
+            waiters: Vec::new(),
+            provider: PhantomData,
+        };
+        Some(window.run(key, self.window, self.executor.clone()))
+    }
+}
+
+struct Flush<K, P: StoreProvider> {
+    pending: Option<Window<K>>,
+    waiters: Waiting<K>,
+    provider: PhantomData<P>,
+}
+impl<K, P> Flush<K, P>
+where
+    K: Eq + Hash + Clone,
+    P: StoreProvider,
+    P::Value: Clone,
+{
+    async fn run(
+        mut self,
+        key: K,
+        window: Duration,
+        executor: Executor<K, P::Value>,
+    ) -> (Result<P::Value, PerformError>, u32) {
+        crate::time::sleep(window).await;
+        self.waiters = self.close();
+        let mut seen = HashSet::new();
+        let keys = std::iter::once(&key)
+            .chain(self.waiters.iter().map(|(key, _, _)| key))
+            .filter(|key| seen.insert(*key))
+            .cloned()
+            .collect();
+        let results = executor(keys).await;
+        let lookup = |key: &K| match &results {
+            Ok(values) => values.get(key).cloned().ok_or(PerformError::Empty),
+            Err(error) => Err(error.clone()),
+        };
+        let settled: Vec<_> = std::mem::take(&mut self.waiters)
+            .into_iter()
+            .map(|(key, id, options)| (id, options, lookup(&key)))
+            .collect();
+        P::shared().settle_each(settled).await;
+        (lookup(&key), 1)
+    }
+    fn close(&mut self) -> Waiting<K> {
+        self.pending
+            .take()
+            .and_then(|pending| pending.borrow_mut().take())
+            .unwrap_or_default()
+    }
+}
+impl<K, P: StoreProvider> Drop for Flush<K, P> {
+    fn drop(&mut self) {
+        let mut waiters = std::mem::take(&mut self.waiters);
+        if let Some(pending) = self.pending.take() {
+            waiters.extend(pending.borrow_mut().take().unwrap_or_default());
+        }
+        let cancelled = waiters
+            .into_iter()
+            .map(|(_, id, options)| (id, options, Err(PerformError::Cancelled)));
+        P::shared().settle_detached(cancelled.collect());
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use crate::{Batcher, Perform, PerformError};
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    mod rows {
+        crate::build_perform!(String);
+    }
+
+    #[tokio::test]
+    async fn batcher_fans_one_batch_call_out_to_each_session() {
+        let batches = Arc::new(AtomicU32::new(0));
+        let counter = batches.clone();
+        let batcher = Batcher::<u32, rows::Provider>::new(Duration::from_millis(10), move |keys| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            async move {
+                let found = keys.into_iter().filter(|key| *key != 404);
+                Ok(found
+                    .map(|key| (key, format!("row {key}")))
+                    .collect::<HashMap<_, _>>())
+            }
+        });
+        let sessions = [
+            rows::Session::activate().await,
+            rows::Session::activate().await,
+            rows::Session::activate().await,
+            rows::Session::activate().await,
+        ];
+        tokio::join!(
+            batcher.perform(&sessions[0], 1),
+            batcher.perform(&sessions[1], 2),
+            batcher.perform(&sessions[2], 1),
+            batcher.perform(&sessions[3], 404),
+        );
+        assert_eq!(batches.load(Ordering::SeqCst), 1);
+        assert_eq!(batcher.pending(), 0);
+        assert_eq!(sessions[0].take().await.unwrap(), "row 1");
+        assert_eq!(sessions[1].take().await.unwrap(), "row 2");
+        assert_eq!(sessions[2].take().await.unwrap(), "row 1");
+        assert!(matches!(sessions[3].take().await, Err(PerformError::Empty)));
+    }
+
+    mod stalled {
+        crate::build_perform!(String);
+    }
+
+    #[tokio::test]
+    async fn followers_are_cancelled_when_the_leader_drops_mid_batch() {
+        let batcher = Batcher::<u32, stalled::Provider>::new(Duration::from_millis(1), |_| {
+            futures::future::pending()
+        });
+        let leader = stalled::Session::activate().await;
+        let follower = stalled::Session::activate().await;
+        let mut lead = Box::pin(batcher.perform(&leader, 1));
+        assert!(futures::poll!(lead.as_mut()).is_pending());
+        batcher.perform(&follower, 2).await;
+        let timed_out = tokio::time::timeout(Duration::from_millis(20), lead.as_mut()).await;
+        assert!(timed_out.is_err());
+        drop(lead);
+        assert!(matches!(
+            follower.take().await,
+            Err(PerformError::Cancelled)
+        ));
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{PerformError, Performer, PerformerMap, StoreProvider};
+use std::hash::Hash;
+
+#[derive(Debug, Clone)]
+pub struct FrameBudget {
+    per_frame: usize,
+    remaining: usize,
+    cursor: usize,
+}
+
+impl FrameBudget {
+    pub fn new(per_frame: usize) -> Self {
+        Self {
+            per_frame,
+            remaining: per_frame,
+            cursor: 0,
+        }
+    }
+    pub fn per_frame(&self) -> usize {
+        self.per_frame
+    }
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining == 0
+    }
+    pub fn begin_frame(&mut self) {
+        self.remaining = self.per_frame;
+    }
+    pub fn try_spend(&mut self) -> bool {
+        match self.remaining {
+            0 => false,
+            _ => {
+                self.remaining -= 1;
+                true
+            }
+        }
+    }
+    pub fn try_take<P: StoreProvider>(
+        &mut self,
+        performer: &mut Performer<P>,
+    ) -> Option<Result<P::Value, PerformError>> {
+        self.try_spend().then(|| performer.try_take())
+    }
+    pub fn drain<K, P, F>(&mut self, performers: &mut PerformerMap<K, P>, mut on_taken: F) -> usize
+    where
+        K: Eq + Hash + Clone,
+        P: StoreProvider,
+        F: FnMut(&K, Result<P::Value, PerformError>),
+    {
+        let keys: Vec<K> = performers.keys().cloned().collect();
+        if keys.is_empty() {
+            return 0;
+        }
+        let start = self.cursor % keys.len();
+        let mut visited = 0;
+        let mut taken = 0;
+        for key in keys[start..].iter().chain(&keys[..start]) {
+            if !self.try_spend() {
+                break;
+            }
+            visited += 1;
+            match performers.try_take(key) {
+                Err(PerformError::Empty | PerformError::Locked) => {}
+                result => {
+                    taken += 1;
+                    on_taken(key, result);
+                }
+            }
+        }
+        self.cursor = start + visited;
+        taken
+    }
+}
+
+impl Default for FrameBudget {
+    fn default() -> Self {
+        Self::new(32)
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::FrameBudget;
+    use crate::PerformerMap;
+    use std::time::Duration;
+
+    mod rows {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    async fn drain_spreads_takes_over_frames() {
+        let mut map = PerformerMap::<u32, rows::Provider>::new();
+        for row in 0..5 {
+            map.get_or_spawn(row, move || async move { row });
+        }
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        let mut budget = FrameBudget::new(2);
+        let mut taken = Vec::new();
+        let mut frames = 0;
+        while taken.len() < 5 {
+            budget.begin_frame();
+            let drained = budget.drain(&mut map, |row, value| taken.push((*row, value.unwrap())));
+            assert!(drained <= 2);
+            assert!(budget.is_exhausted() || taken.len() == 5);
+            frames += 1;
+        }
+        assert_eq!(frames, 3);
+        taken.sort();
+        assert_eq!(taken, (0..5).map(|row| (row, row)).collect::<Vec<_>>());
+    }
+}
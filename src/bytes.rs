@@ -0,0 +1,129 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[cfg(target_arch = "wasm32")]
+thread_local! {
+    static TRANSFER: std::cell::RefCell<Option<Vec<js_sys::ArrayBuffer>>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn collect_transfers<R>(f: impl FnOnce() -> R) -> (R, js_sys::Array) {
+    let outer = TRANSFER.with(|transfer| transfer.borrow_mut().replace(Vec::new()));
+    let result = f();
+    let buffers = TRANSFER.with(|transfer| std::mem::replace(&mut *transfer.borrow_mut(), outer));
+    (result, buffers.unwrap_or_default().into_iter().collect())
+}
+
+#[derive(Debug, Clone)]
+pub struct Bytes {
+    #[cfg(target_arch = "wasm32")]
+    buffer: js_sys::ArrayBuffer,
+    #[cfg(not(target_arch = "wasm32"))]
+    data: Vec<u8>,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Bytes {
+    pub fn from_buffer(buffer: js_sys::ArrayBuffer) -> Self {
+        Self { buffer }
+    }
+    pub fn buffer(&self) -> &js_sys::ArrayBuffer {
+        &self.buffer
+    }
+    pub fn into_buffer(self) -> js_sys::ArrayBuffer {
+        self.buffer
+    }
+
+    pub fn len(&self) -> usize {
+        self.buffer.byte_length() as usize
+    }
+    pub fn to_vec(&self) -> Vec<u8> {
+        js_sys::Uint8Array::new(&self.buffer).to_vec()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Bytes {
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.data.clone()
+    }
+}
+
+impl Bytes {
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl From<&[u8]> for Bytes {
+    #[cfg(target_arch = "wasm32")]
+    fn from(data: &[u8]) -> Self {
+        Self::from_buffer(js_sys::Uint8Array::from(data).buffer())
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    fn from(data: &[u8]) -> Self {
+        Self {
+            data: data.to_vec(),
+        }
+    }
+}
+
+impl From<Vec<u8>> for Bytes {
+    #[cfg(target_arch = "wasm32")]
+    fn from(data: Vec<u8>) -> Self {
+        Self::from(data.as_slice())
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    fn from(data: Vec<u8>) -> Self {
+        Self { data }
+    }
+}
+
+impl Serialize for Bytes {
+    #[cfg(target_arch = "wasm32")]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        TRANSFER.with(|transfer| {
+            if let Some(buffers) = transfer.borrow_mut().as_mut() {
+                if !buffers.contains(&self.buffer) {
+                    buffers.push(self.buffer.clone());
+                }
+            }
+        });
+        serde_wasm_bindgen::preserve::serialize(&self.buffer, serializer)
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.data.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Bytes {
+    #[cfg(target_arch = "wasm32")]
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        serde_wasm_bindgen::preserve::deserialize(deserializer).map(Self::from_buffer)
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::deserialize(deserializer).map(|data| Self { data })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Bytes;
+
+    #[test]
+    fn bytes_round_trip_through_serde() {
+        let bytes = Bytes::from(vec![0u8, 127, 255]);
+        assert_eq!(bytes.len(), 3);
+        assert!(!bytes.is_empty());
+
+        let json = serde_json::to_string(&bytes).unwrap();
+        let decoded: Bytes = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.to_vec(), vec![0, 127, 255]);
+        assert!(Bytes::from(&[][..]).is_empty());
+    }
+}
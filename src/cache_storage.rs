@@ -0,0 +1,64 @@
+use crate::js::resolve;
+use crate::store::lock_and_do_mut;
+use crate::{CacheStatus, Perform, PerformError, PerformHandle, Session, StoreProvider};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::future::Future;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{Cache, CacheStorage, Response};
+
+async fn open(name: &str) -> Result<Cache, PerformError> {
+    let caches = js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str("caches"))?
+        .dyn_into::<CacheStorage>()?;
+    Ok(resolve(caches.open(name)).await?.dyn_into::<Cache>()?)
+}
+
+async fn lookup<T: DeserializeOwned>(cache: &Cache, key: &str) -> Result<Option<T>, PerformError> {
+    let matched = resolve(cache.match_with_str(key)).await?;
+    let Ok(response) = matched.dyn_into::<Response>() else {
+        return Ok(None);
+    };
+    let json = resolve(response.json()?).await?;
+    Ok(serde_wasm_bindgen::from_value(json).ok())
+}
+
+async fn store<T: Serialize>(cache: &Cache, key: &str, value: &T) -> Result<(), PerformError> {
+    let json = serde_wasm_bindgen::to_value(value).map_err(JsValue::from)?;
+    let body = js_sys::JSON::stringify(&json)?;
+    let response = Response::new_with_opt_str(body.as_string().as_deref())?;
+    resolve(cache.put_with_str(key, &response)).await?;
+    Ok(())
+}
+
+pub async fn evict(name: &str, key: &str) -> Result<bool, PerformError> {
+    let deleted = resolve(open(name).await?.delete_with_str(key)).await?;
+    Ok(deleted.as_bool().unwrap_or(false))
+}
+
+impl<P: StoreProvider> Session<P>
+where
+    P::Value: Serialize + DeserializeOwned,
+{
+    pub fn perform_cached<Fut>(&self, name: &str, key: &str, fut: Fut) -> PerformHandle
+    where
+        Fut: Future<Output = Result<P::Value, PerformError>> + 'static,
+    {
+        let (id, name, key) = (*self.id(), name.to_string(), key.to_string());
+        self.perform_result_with_spawn_local(async move {
+            let cache = open(&name).await.ok();
+            if let Some(cache) = &cache {
+                if let Ok(Some(value)) = lookup(cache, &key).await {
+                    lock_and_do_mut(P::shared(), |store| store.note_cache(id, CacheStatus::Hit))
+                        .await;
+                    return Ok(value);
+                }
+            }
+            let value = fut.await?;
+            if let Some(cache) = &cache {
+                let _ = store(cache, &key, &value).await;
+                lock_and_do_mut(P::shared(), |store| store.note_cache(id, CacheStatus::Miss)).await;
+            }
+            Ok(value)
+        })
+    }
+}
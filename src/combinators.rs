@@ -0,0 +1,274 @@
+use crate::session::Dispatch;
+use crate::store::{lock_and_do_mut, try_lock_and_do_mut};
+use crate::{InsertOptions, Perform, PerformError, PerformHandle, Session, StoreProvider, Uuid};
+use futures::future::{join_all, select_all};
+use futures::stream::{FuturesUnordered, Stream, StreamExt};
+use std::future::Future;
+use std::pin::Pin;
+
+impl<P: StoreProvider> Session<P> {
+    pub async fn perform_race<I, Fut>(&self, futs: I)
+    where
+        I: IntoIterator<Item = Fut>,
+        Fut: Future<Output = P::Value> + 'static,
+    {
+        self.perform_result(race(futs.into_iter().map(Box::pin).collect()))
+            .await
+    }
+    pub fn perform_race_with_spawn_local<I, Fut>(&self, futs: I) -> PerformHandle
+    where
+        I: IntoIterator<Item = Fut>,
+        Fut: Future<Output = P::Value> + 'static,
+    {
+        self.perform_result_with_spawn_local(race(futs.into_iter().map(Box::pin).collect()))
+    }
+}
+
+impl<P: StoreProvider> Session<P>
+where
+    P::Value: Clone,
+{
+    pub fn try_peek(&self) -> Result<P::Value, PerformError> {
+        try_lock_and_do_mut(P::shared(), |store| {
+            store.peek(self.id()).unwrap_or(Err(PerformError::Empty))
+        })
+    }
+    pub async fn until_ready(&self) -> Result<P::Value, PerformError> {
+        until_ready::<P>(*self.id()).await
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn then<Q, F, Fut>(&self, f: F) -> Session<Q>
+    where
+        Q: StoreProvider,
+        F: FnOnce(P::Value) -> Fut + 'static + Send,
+        Fut: Future<Output = Q::Value> + 'static + Send,
+        P::Value: Send,
+    {
+        let (upstream, next) = (*self.id(), Session::<Q>::try_activate());
+        next.perform_result_spawned(async move { Ok(f(until_ready::<P>(upstream).await?).await) });
+        next
+    }
+    #[cfg(target_arch = "wasm32")]
+    pub fn then<Q, F, Fut>(&self, f: F) -> Session<Q>
+    where
+        Q: StoreProvider,
+        F: FnOnce(P::Value) -> Fut + 'static,
+        Fut: Future<Output = Q::Value> + 'static,
+    {
+        self.then_with_spawn_local(f)
+    }
+    pub fn then_with_spawn_local<Q, F, Fut>(&self, f: F) -> Session<Q>
+    where
+        Q: StoreProvider,
+        F: FnOnce(P::Value) -> Fut + 'static,
+        Fut: Future<Output = Q::Value> + 'static,
+    {
+        let (upstream, next) = (*self.id(), Session::<Q>::try_activate());
+        next.perform_result_with_spawn_local(async move {
+            Ok(f(until_ready::<P>(upstream).await?).await)
+        });
+        next
+    }
+}
+
+impl<P, T> Session<P>
+where
+    P: StoreProvider<Value = Vec<T>>,
+    T: 'static,
+{
+    pub async fn perform_all<I, Fut>(&self, futs: I)
+    where
+        I: IntoIterator<Item = Fut>,
+        Fut: Future<Output = T> + 'static,
+    {
+        let joined = join_all(futs);
+        self.perform_result(async move { Ok(joined.await) }).await
+    }
+    pub fn perform_all_with_spawn_local<I, Fut>(&self, futs: I) -> PerformHandle
+    where
+        I: IntoIterator<Item = Fut>,
+        Fut: Future<Output = T> + 'static,
+    {
+        let joined = join_all(futs);
+        self.perform_result_with_spawn_local(async move { Ok(joined.await) })
+    }
+    pub async fn perform_unordered<I, Fut>(&self, futs: I)
+    where
+        I: IntoIterator<Item = Fut>,
+        Fut: Future<Output = T> + 'static,
+    {
+        let pending: FuturesUnordered<_> = futs.into_iter().collect();
+        let id = *self.id();
+        self.perform_staged(
+            move |options| accumulate::<P, _, _>(id, pending, options),
+            Dispatch::default(),
+        )
+        .await
+    }
+    pub fn perform_unordered_with_spawn_local<I, Fut>(&self, futs: I) -> PerformHandle
+    where
+        I: IntoIterator<Item = Fut>,
+        Fut: Future<Output = T> + 'static,
+    {
+        let pending: FuturesUnordered<_> = futs.into_iter().collect();
+        let id = *self.id();
+        self.spawn_staged_with_spawn_local(
+            move |options| accumulate::<P, _, _>(id, pending, options),
+            Dispatch::default(),
+        )
+    }
+    pub fn try_take_partial(&self) -> Result<Vec<T>, PerformError> {
+        try_lock_and_do_mut(P::shared(), |store| Ok(store.take_appended(self.id())))
+    }
+    pub async fn take_partial(&self) -> Vec<T> {
+        lock_and_do_mut(P::shared(), |store| store.take_appended(self.id())).await
+    }
+}
+
+pub(crate) async fn accumulate<P, T, S>(
+    id: Uuid,
+    items: S,
+    options: InsertOptions,
+) -> (Result<Vec<T>, PerformError>, u32)
+where
+    P: StoreProvider<Value = Vec<T>>,
+    T: 'static,
+    S: Stream<Item = T>,
+{
+    futures::pin_mut!(items);
+    while let Some(item) = items.next().await {
+        let appended = lock_and_do_mut(P::shared(), |store| store.append(id, item, options)).await;
+        if let Err(error) = appended {
+            return (Err(error), 1);
+        }
+    }
+    let rest = lock_and_do_mut(P::shared(), |store| store.take_appended(&id)).await;
+    (Ok(rest), 1)
+}
+
+async fn until_ready<P>(id: Uuid) -> Result<P::Value, PerformError>
+where
+    P: StoreProvider,
+    P::Value: Clone,
+{
+    loop {
+        let completed = P::shared().completed();
+        if let Some(result) = lock_and_do_mut(P::shared(), |store| store.peek(&id)).await {
+            return result;
+        }
+        completed.await;
+    }
+}
+
+async fn race<Fut: Future>(futs: Vec<Pin<Box<Fut>>>) -> Result<Fut::Output, PerformError> {
+    match futs.is_empty() {
+        true => Err(PerformError::Empty),
+        false => Ok(select_all(futs).await.0),
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use crate::Perform;
+    use std::time::Duration;
+
+    mod raced {
+        crate::build_perform!(u32);
+    }
+    mod batch {
+        crate::build_perform!(Vec<u32>);
+    }
+    mod login {
+        crate::build_perform!(Result<String, String>);
+    }
+    mod profile {
+        crate::build_perform!(usize);
+    }
+
+    async fn arrived(session: &batch::Session, count: usize) -> Vec<u32> {
+        let mut arrived = Vec::new();
+        while arrived.len() < count {
+            arrived.extend(session.take_partial().await);
+            tokio::task::yield_now().await;
+        }
+        arrived
+    }
+
+    #[tokio::test]
+    async fn then_runs_each_stage_after_the_previous_resolves() {
+        let login = login::Session::activate().await;
+        let profile: profile::Session = login.then(|token: Result<String, String>| async move {
+            token.map_or(0, |token| token.len())
+        });
+        let avatar: profile::Session = profile.then(|length| async move { length * 2 });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(matches!(
+            profile.try_take(),
+            Err(crate::PerformError::Empty)
+        ));
+
+        login.perform_spawned(async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            Ok("secret".to_string())
+        });
+        assert_eq!(avatar.until_ready().await.unwrap(), 12);
+        assert_eq!(profile.try_peek().unwrap(), 6);
+        assert_eq!(login.take().await.unwrap().unwrap(), "secret");
+    }
+
+    #[tokio::test]
+    async fn perform_all_stores_every_output_in_order() {
+        let session = batch::Session::activate().await;
+        let futs = [30, 10, 20].map(|millis| async move {
+            tokio::time::sleep(Duration::from_millis(millis)).await;
+            millis as u32
+        });
+        session.perform_all(futs).await;
+        assert_eq!(session.take().await.unwrap(), vec![30, 10, 20]);
+    }
+
+    #[tokio::test]
+    async fn perform_unordered_accumulates_outputs_as_they_arrive() {
+        let session = batch::Session::activate().await;
+        let [(slow, first), (fast, second), (middle, third)] =
+            [(); 3].map(|()| futures::channel::oneshot::channel::<u32>());
+        let futs = [first, second, third].map(|output| async move { output.await.unwrap() });
+        let partial = async {
+            fast.send(10).unwrap();
+            let mut partial = arrived(&session, 1).await;
+            middle.send(30).unwrap();
+            partial.extend(arrived(&session, 1).await);
+            slow.send(60).unwrap();
+            partial
+        };
+        let ((), partial) = tokio::join!(session.perform_unordered(futs), partial);
+        assert_eq!(partial, vec![10, 30]);
+        assert_eq!(session.take().await.unwrap(), vec![60]);
+        assert!(session.try_take_partial().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn perform_race_keeps_the_first_completion() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+        use std::sync::Arc;
+        let session = raced::Session::activate().await;
+        let finished = Arc::new(AtomicU32::new(0));
+        let futs = [30, 5, 20].map(|millis| {
+            let finished = finished.clone();
+            async move {
+                tokio::time::sleep(Duration::from_millis(millis)).await;
+                finished.fetch_add(1, Ordering::SeqCst);
+                millis as u32
+            }
+        });
+        session.perform_race(futs).await;
+        assert_eq!(session.take().await.unwrap(), 5);
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        assert_eq!(finished.load(Ordering::SeqCst), 1);
+
+        session
+            .perform_race(Vec::<std::future::Ready<u32>>::new())
+            .await;
+        assert!(session.take().await.is_err());
+    }
+}
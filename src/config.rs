@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EvictionPolicy {
+    #[default]
+    RejectNew,
+    LeastRecentlyUsed,
+    OldestCompleted,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CompletionOrder {
+    #[default]
+    Any,
+    Issued,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitBreaker {
+    pub threshold: u32,
+    pub cool_down: Duration,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self {
+            threshold: 5,
+            cool_down: Duration::from_secs(30),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RateLimitOverflow {
+    #[default]
+    Queue,
+    Coalesce,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ThrottleMode {
+    #[default]
+    Coalesce,
+    Drop,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub permits: u32,
+    pub per: Duration,
+    pub overflow: RateLimitOverflow,
+}
+
+impl RateLimit {
+    pub fn per_second(permits: u32) -> Self {
+        Self {
+            permits,
+            per: Duration::from_secs(1),
+            overflow: RateLimitOverflow::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PerformConfig {
+    pub capacity: Option<usize>,
+    pub eviction: EvictionPolicy,
+    pub ttl: Option<Duration>,
+    pub lock_retry: u32,
+    pub completion_order: CompletionOrder,
+    pub circuit_breaker: Option<CircuitBreaker>,
+    pub rate_limit: Option<RateLimit>,
+    pub timeout: Option<Duration>,
+    pub max_in_flight: Option<usize>,
+    pub deferred: bool,
+    pub queue_offline: bool,
+    pub event_capacity: Option<usize>,
+}
@@ -0,0 +1,144 @@
+#[cfg(all(target_arch = "wasm32", feature = "web-sys"))]
+mod browser {
+    use std::cell::RefCell;
+    use wasm_bindgen::closure::Closure;
+    use wasm_bindgen::JsCast;
+
+    pub(crate) struct Listener {
+        target: web_sys::EventTarget,
+        event: &'static str,
+        callback: Closure<dyn FnMut()>,
+    }
+
+    impl Listener {
+        pub(crate) fn new<F>(target: web_sys::EventTarget, event: &'static str, f: F) -> Self
+        where
+            F: FnMut() + 'static,
+        {
+            let callback = Closure::<dyn FnMut()>::new(f);
+            let _ =
+                target.add_event_listener_with_callback(event, callback.as_ref().unchecked_ref());
+            Self {
+                target,
+                event,
+                callback,
+            }
+        }
+    }
+
+    impl Drop for Listener {
+        fn drop(&mut self) {
+            let _ = self.target.remove_event_listener_with_callback(
+                self.event,
+                self.callback.as_ref().unchecked_ref(),
+            );
+        }
+    }
+
+    pub(crate) async fn once(target: web_sys::EventTarget, event: &'static str) {
+        let (sender, receiver) = futures::channel::oneshot::channel();
+        let sender = RefCell::new(Some(sender));
+        let _listener = Listener::new(target, event, move || {
+            if let Some(sender) = sender.borrow_mut().take() {
+                let _ = sender.send(());
+            }
+        });
+        let _ = receiver.await;
+    }
+
+    pub fn is_online() -> bool {
+        web_sys::window().is_none_or(|window| window.navigator().on_line())
+    }
+
+    pub async fn reachable() -> bool {
+        is_online()
+    }
+
+    pub async fn online() {
+        if let Some(window) = web_sys::window().filter(|_| !is_online()) {
+            once(window.into(), "online").await
+        }
+    }
+}
+#[cfg(all(target_arch = "wasm32", feature = "web-sys"))]
+pub use browser::{is_online, online, reachable};
+
+#[cfg(not(target_arch = "wasm32"))]
+mod probe {
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+    const PROBE_INTERVAL: Duration = Duration::from_secs(1);
+
+    static PROBE: Mutex<Option<String>> = Mutex::new(None);
+
+    pub fn set_probe(address: Option<String>) {
+        *PROBE
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = address;
+    }
+
+    pub async fn reachable() -> bool {
+        let probe = PROBE
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone();
+        let Some(address) = probe else {
+            return true;
+        };
+        let connect = tokio::net::TcpStream::connect(address);
+        matches!(
+            tokio::time::timeout(PROBE_TIMEOUT, connect).await,
+            Ok(Ok(_))
+        )
+    }
+
+    pub async fn online() {
+        while !reachable().await {
+            crate::time::sleep(PROBE_INTERVAL).await;
+        }
+    }
+}
+#[cfg(not(target_arch = "wasm32"))]
+pub use probe::{online, reachable, set_probe};
+
+#[cfg(not(all(target_arch = "wasm32", feature = "web-sys")))]
+pub fn is_online() -> bool {
+    true
+}
+#[cfg(all(target_arch = "wasm32", not(feature = "web-sys")))]
+pub async fn reachable() -> bool {
+    true
+}
+#[cfg(all(target_arch = "wasm32", not(feature = "web-sys")))]
+pub async fn online() {}
+
+#[cfg(test)]
+mod tests {
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn native_is_always_online() {
+        assert!(super::is_online());
+        super::online().await;
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn reachable_probes_the_configured_address() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let open = listener.local_addr().unwrap().to_string();
+        super::set_probe(Some(open));
+        assert!(super::reachable().await);
+
+        drop(listener);
+        let closed = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = closed.local_addr().unwrap().to_string();
+        drop(closed);
+        super::set_probe(Some(address));
+        assert!(!super::reachable().await);
+
+        super::set_probe(None);
+        assert!(super::reachable().await);
+    }
+}
@@ -0,0 +1,123 @@
+use crate::{Completion, Perform, PerformError, Session, StoreProvider, TaskMessage, Uuid};
+use futures::future::{AbortHandle, Abortable};
+use futures::StreamExt;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::rc::Rc;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use web_sys::{BroadcastChannel, MessageEvent};
+
+type Bound = Rc<RefCell<HashMap<Uuid, String>>>;
+
+#[derive(Serialize, Deserialize)]
+struct Mirrored<T> {
+    key: String,
+    message: TaskMessage<T>,
+}
+
+pub struct CrossTab<P: StoreProvider> {
+    channel: BroadcastChannel,
+    namespace: String,
+    bound: Bound,
+    mirror: AbortHandle,
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+    provider: PhantomData<P>,
+}
+
+impl<P: StoreProvider> CrossTab<P>
+where
+    P::Value: Serialize + DeserializeOwned + Clone,
+{
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    pub fn open(channel: &str, namespace: &str) -> Result<Self, PerformError> {
+        let channel = BroadcastChannel::new(channel)?;
+        let namespace = namespace.to_string();
+        let bound = Bound::default();
+
+        let on_message = Closure::<dyn FnMut(MessageEvent)>::new({
+            let (bound, namespace) = (bound.clone(), namespace.clone());
+            move |event: MessageEvent| {
+                let Ok(mirrored) =
+                    serde_wasm_bindgen::from_value::<Mirrored<P::Value>>(event.data())
+                else {
+                    return;
+                };
+                if mirrored.message.store_id != namespace {
+                    return;
+                }
+                let id = bound
+                    .borrow()
+                    .iter()
+                    .find_map(|(id, key)| (*key == mirrored.key).then_some(*id));
+                if let (Some(id), Ok(value)) = (id, mirrored.message.into_result()) {
+                    crate::spawn_local(async move {
+                        let _ = P::shared().hydrate(id, value).await;
+                    });
+                }
+            }
+        });
+        channel.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+
+        let (mirror, registration) = AbortHandle::new_pair();
+        let sender = channel.clone();
+        let (outgoing, store_id) = (bound.clone(), namespace.clone());
+        let completions = P::shared().completions();
+        crate::spawn_local(async move {
+            let mirrored = completions.for_each(move |completion| {
+                let Completion {
+                    id,
+                    task_id,
+                    result,
+                } = completion;
+                let key = outgoing.borrow().get(&id).cloned();
+                if let (Some(key), Ok(value)) = (key, result) {
+                    let mirrored = Mirrored {
+                        key,
+                        message: TaskMessage::new(task_id, store_id.as_str(), Ok(value)),
+                    };
+                    if let Ok(message) = serde_wasm_bindgen::to_value(&mirrored) {
+                        let _ = sender.post_message(&message);
+                    }
+                }
+                futures::future::ready(())
+            });
+            let _ = Abortable::new(mirrored, registration).await;
+        });
+
+        Ok(Self {
+            channel,
+            namespace,
+            bound,
+            mirror,
+            _on_message: on_message,
+            provider: PhantomData,
+        })
+    }
+
+    pub fn bind(&self, session: &Session<P>, key: impl Into<String>) {
+        self.bound.borrow_mut().insert(*session.id(), key.into());
+    }
+
+    pub fn unbind(&self, session: &Session<P>) -> bool {
+        self.bound.borrow_mut().remove(session.id()).is_some()
+    }
+
+    pub fn is_bound(&self, session: &Session<P>) -> bool {
+        self.bound.borrow().contains_key(session.id())
+    }
+}
+
+impl<P: StoreProvider> Drop for CrossTab<P> {
+    fn drop(&mut self) {
+        self.mirror.abort();
+        self.channel.set_onmessage(None);
+        self.channel.close();
+    }
+}
@@ -0,0 +1,144 @@
+use crate::session::Dispatch;
+use crate::store::Waiters;
+use crate::{Perform, PerformError, PerformHandle, Session, SharedStore, StoreProvider};
+use std::future::Future;
+
+impl<P: StoreProvider> Session<P>
+where
+    P::Value: Clone,
+{
+    pub async fn perform_dedup<F, Fut>(&self, key: &str, factory: F)
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = P::Value> + 'static,
+    {
+        if let Some(leader) = self.lead(key) {
+            let fut = leader.run(factory());
+            self.perform_attempted(fut, Dispatch::default()).await
+        }
+    }
+    pub fn perform_dedup_with_spawn_local<F, Fut>(
+        &self,
+        key: &str,
+        factory: F,
+    ) -> Option<PerformHandle>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = P::Value> + 'static,
+    {
+        let leader = self.lead(key)?;
+        let fut = leader.run(factory());
+        Some(self.spawn_staged_with_spawn_local(|_| fut, Dispatch::default()))
+    }
+    fn lead(&self, key: &str) -> Option<Leader<P::Value>> {
+        let shared = P::shared();
+        match shared.join_dedup(key, *self.id(), self.next_generation()) {
+            true => Some(Leader {
+                shared,
+                key: Some(key.to_string()),
+            }),
+            false => None,
+        }
+    }
+}
+
+struct Leader<T: Send + 'static> {
+    shared: &'static SharedStore<T>,
+    key: Option<String>,
+}
+impl<T: Clone + Send + 'static> Leader<T> {
+    async fn run<Fut>(mut self, fut: Fut) -> (Result<T, PerformError>, u32)
+    where
+        Fut: Future<Output = T>,
+    {
+        let value = fut.await;
+        let waiters = self.waiters();
+        self.shared.settle(waiters, Ok(value.clone())).await;
+        (Ok(value), 1)
+    }
+}
+impl<T: Send + 'static> Leader<T> {
+    fn waiters(&mut self) -> Waiters {
+        self.key
+            .take()
+            .map(|key| self.shared.leave_dedup(&key))
+            .unwrap_or_default()
+    }
+}
+impl<T: Send + 'static> Drop for Leader<T> {
+    fn drop(&mut self) {
+        if self.key.is_some() {
+            let cancelled = self.waiters().into_iter();
+            let cancelled =
+                cancelled.map(|(id, options)| (id, options, Err(PerformError::Cancelled)));
+            self.shared.settle_detached(cancelled.collect());
+        }
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use crate::Perform;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    mod fetched {
+        crate::build_perform!(String);
+    }
+
+    #[tokio::test]
+    async fn perform_dedup_shares_one_in_flight_future() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let factory = || {
+            let calls = calls.clone();
+            move || async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                "body".to_string()
+            }
+        };
+        let sessions = [
+            fetched::Session::activate().await,
+            fetched::Session::activate().await,
+            fetched::Session::activate().await,
+        ];
+        tokio::join!(
+            sessions[0].perform_dedup("/ip", factory()),
+            sessions[1].perform_dedup("/ip", factory()),
+            sessions[2].perform_dedup("/other", factory()),
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        for session in &sessions {
+            assert_eq!(session.take().await.unwrap(), "body");
+        }
+
+        sessions[0].perform_dedup("/ip", factory()).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    mod abandoned {
+        crate::build_perform!(String);
+    }
+
+    #[tokio::test]
+    async fn followers_are_cancelled_when_the_leader_drops_under_a_held_lock() {
+        use crate::{PerformError, StoreProvider};
+        let leader = abandoned::Session::activate().await;
+        let follower = abandoned::Session::activate().await;
+        let mut lead = Box::pin(leader.perform_dedup("/slow", futures::future::pending));
+        assert!(futures::poll!(lead.as_mut()).is_pending());
+        follower
+            .perform_dedup("/slow", || async { String::new() })
+            .await;
+        let held = abandoned::Provider::store().lock().await;
+        let settled = abandoned::Provider::shared().completed();
+        drop(lead);
+        drop(held);
+        settled.await;
+        assert!(matches!(
+            follower.take().await,
+            Err(PerformError::Cancelled)
+        ));
+    }
+}
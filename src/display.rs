@@ -0,0 +1,105 @@
+use crate::{PerformError, Performer, StoreProvider};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BadgeState {
+    Idle,
+    Loading,
+    Success,
+    Error,
+}
+
+pub trait PerformerExt {
+    type Value;
+
+    fn map_view<R>(
+        &self,
+        idle: R,
+        loading: R,
+        ready: impl FnOnce(&Self::Value) -> R,
+        failed: impl FnOnce(&PerformError) -> R,
+    ) -> R;
+
+    fn as_badge_state(&self) -> BadgeState {
+        self.map_view(
+            BadgeState::Idle,
+            BadgeState::Loading,
+            |_| BadgeState::Success,
+            |_| BadgeState::Error,
+        )
+    }
+    fn as_text(&self, ready: impl FnOnce(&Self::Value) -> String) -> String {
+        self.map_view(
+            String::new(),
+            "Loading...".to_string(),
+            ready,
+            PerformError::to_string,
+        )
+    }
+    fn as_text_or(
+        &self,
+        idle: &str,
+        loading: &str,
+        ready: impl FnOnce(&Self::Value) -> String,
+    ) -> String {
+        self.map_view(
+            idle.to_string(),
+            loading.to_string(),
+            ready,
+            PerformError::to_string,
+        )
+    }
+}
+
+impl<P: StoreProvider> PerformerExt for Performer<P> {
+    type Value = P::Value;
+
+    fn map_view<R>(
+        &self,
+        idle: R,
+        loading: R,
+        ready: impl FnOnce(&P::Value) -> R,
+        failed: impl FnOnce(&PerformError) -> R,
+    ) -> R {
+        let state = self.state();
+        if let Some(error) = state.error() {
+            return failed(error);
+        }
+        match (state.is_in_flight(), self.cached()) {
+            (true, _) => loading,
+            (false, Some(value)) => ready(value),
+            (false, None) => idle,
+        }
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::{BadgeState, PerformerExt};
+    use crate::Perform;
+    use std::time::Duration;
+
+    mod shown {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    async fn views_follow_the_performer_through_its_states() {
+        let mut performer = shown::Performer::new(shown::Session::activate().await);
+        let text = |performer: &shown::Performer| performer.as_text(|value| format!("#{value}"));
+        assert_eq!(performer.as_badge_state(), BadgeState::Idle);
+        assert_eq!(text(&performer), "");
+
+        performer.perform_latest_spawned(async { 5 });
+        assert_eq!(performer.as_badge_state(), BadgeState::Loading);
+        assert_eq!(text(&performer), "Loading...");
+
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        performer.poll();
+        assert_eq!(performer.as_badge_state(), BadgeState::Success);
+        assert_eq!(text(&performer), "#5");
+        assert_eq!(
+            performer.as_text_or("-", "...", |value| value.to_string()),
+            "5"
+        );
+    }
+}
@@ -0,0 +1,627 @@
+use crate::{
+    MaybeSend, Page, PagedLoader, PerformError, Performer, PerformerState, PollAdapter, Session,
+    StoreProvider,
+};
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RepaintDriver {
+    min: Duration,
+    max: Duration,
+    current: Option<Duration>,
+}
+
+impl Default for RepaintDriver {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(16), Duration::from_millis(500))
+    }
+}
+
+impl RepaintDriver {
+    pub fn new(min: Duration, max: Duration) -> Self {
+        Self {
+            min,
+            max: max.max(min),
+            current: None,
+        }
+    }
+    pub fn interval(&self) -> Option<Duration> {
+        self.current
+    }
+    pub fn is_idle(&self) -> bool {
+        self.current.is_none()
+    }
+    pub fn drive<I>(&mut self, ctx: &egui::Context, pending: I) -> bool
+    where
+        I: IntoIterator<Item = bool>,
+    {
+        let interval = self.next(pending.into_iter().any(|pending| pending));
+        if let Some(interval) = interval {
+            ctx.request_repaint_after(interval);
+        }
+        interval.is_some()
+    }
+    fn next(&mut self, pending: bool) -> Option<Duration> {
+        self.current = match (pending, self.current) {
+            (false, _) => None,
+            (true, None) => Some(self.min),
+            (true, Some(current)) => Some((current * 2).min(self.max)),
+        };
+        self.current
+    }
+}
+
+impl PollAdapter for egui::Context {
+    fn on_pending(&self) {
+        self.request_repaint();
+    }
+    fn on_complete(&self) {
+        self.request_repaint();
+    }
+}
+
+pub struct AsyncLabel<P: StoreProvider> {
+    performer: Performer<P>,
+    value: Option<P::Value>,
+    placeholder: String,
+}
+
+impl<P: StoreProvider> AsyncLabel<P>
+where
+    P::Value: Display,
+{
+    pub fn new(performer: Performer<P>, placeholder: impl Into<String>) -> Self {
+        Self {
+            performer,
+            value: None,
+            placeholder: placeholder.into(),
+        }
+    }
+    pub fn performer_mut(&mut self) -> &mut Performer<P> {
+        &mut self.performer
+    }
+    pub fn value(&self) -> Option<&P::Value> {
+        self.value.as_ref()
+    }
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> egui::Response {
+        if let Ok(value) = self.performer.try_take() {
+            self.value = Some(value);
+        }
+        match (&self.value, self.performer.state().error()) {
+            (Some(value), _) => ui.label(value.to_string()),
+            (None, Some(error)) => ui.colored_label(ui.visuals().error_fg_color, error.to_string()),
+            (None, None) => pending_label(ui, &self.placeholder, self.performer.is_pending()),
+        }
+    }
+}
+
+pub struct AsyncTextField<P: StoreProvider> {
+    performer: Performer<P>,
+    text: String,
+    placeholder: String,
+}
+
+impl<P: StoreProvider> AsyncTextField<P>
+where
+    P::Value: Display,
+{
+    pub fn new(performer: Performer<P>, placeholder: impl Into<String>) -> Self {
+        Self {
+            performer,
+            text: String::new(),
+            placeholder: placeholder.into(),
+        }
+    }
+    pub fn performer_mut(&mut self) -> &mut Performer<P> {
+        &mut self.performer
+    }
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> egui::Response {
+        if let Ok(value) = self.performer.try_take() {
+            self.text = value.to_string();
+        }
+        let edit = egui::TextEdit::singleline(&mut self.text).hint_text(self.placeholder.as_str());
+        ui.add_enabled(!self.performer.is_pending(), edit)
+    }
+}
+
+pub struct AsyncButton<P: StoreProvider> {
+    performer: Performer<P>,
+    text: String,
+}
+
+impl<P: StoreProvider> AsyncButton<P> {
+    pub fn new(performer: Performer<P>, text: impl Into<String>) -> Self {
+        Self {
+            performer,
+            text: text.into(),
+        }
+    }
+    pub fn performer_mut(&mut self) -> &mut Performer<P> {
+        &mut self.performer
+    }
+    pub fn ui<F, Fut, H>(&mut self, ui: &mut egui::Ui, factory: F, on_result: H) -> egui::Response
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = P::Value> + 'static + MaybeSend,
+        H: FnOnce(Result<P::Value, PerformError>),
+    {
+        let response = self.show(ui, on_result);
+        if response.clicked() {
+            self.performer.perform_latest_spawned(factory());
+        }
+        response
+    }
+    fn show<H>(&mut self, ui: &mut egui::Ui, on_result: H) -> egui::Response
+    where
+        H: FnOnce(Result<P::Value, PerformError>),
+    {
+        match self.performer.try_take() {
+            Ok(value) => on_result(Ok(value)),
+            Err(PerformError::Empty | PerformError::Locked) => {}
+            Err(error) => {
+                self.performer.reset();
+                on_result(Err(error));
+            }
+        }
+        let pending = self.performer.is_pending();
+        ui.add_enabled_ui(!pending, |ui| {
+            ui.horizontal(|ui| {
+                if pending {
+                    ui.spinner();
+                }
+                ui.button(self.text.as_str())
+            })
+            .inner
+        })
+        .inner
+    }
+}
+
+pub struct ElapsedSpinner {
+    threshold: Duration,
+    slow_text: String,
+}
+
+impl Default for ElapsedSpinner {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(5))
+    }
+}
+
+impl ElapsedSpinner {
+    pub fn new(threshold: Duration) -> Self {
+        Self {
+            threshold,
+            slow_text: "taking longer than expected".to_string(),
+        }
+    }
+    pub fn slow_text(mut self, text: impl Into<String>) -> Self {
+        self.slow_text = text.into();
+        self
+    }
+    pub fn ui<P: StoreProvider>(
+        &self,
+        ui: &mut egui::Ui,
+        performer: &Performer<P>,
+    ) -> egui::Response {
+        match performer.state() {
+            PerformerState::InFlight { since } => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(self.caption(since.elapsed()));
+                })
+                .response
+            }
+            PerformerState::Ready {
+                since,
+                completed_at,
+            } => ui.label(format!(
+                "{:.1}s",
+                completed_at.saturating_duration_since(since).as_secs_f32()
+            )),
+            _ => ui.allocate_response(egui::Vec2::ZERO, egui::Sense::hover()),
+        }
+    }
+    fn caption(&self, elapsed: Duration) -> String {
+        match elapsed < self.threshold {
+            true => format!("{:.1}s", elapsed.as_secs_f32()),
+            false => format!("{} ({:.0}s)", self.slow_text, elapsed.as_secs_f32()),
+        }
+    }
+}
+
+pub struct FreshnessHighlight {
+    color: egui::Color32,
+    fade: f32,
+    weight: f32,
+    landed: Arc<AtomicBool>,
+}
+
+impl Default for FreshnessHighlight {
+    fn default() -> Self {
+        Self::new(egui::Color32::DARK_GREEN)
+    }
+}
+
+impl FreshnessHighlight {
+    pub fn new(color: egui::Color32) -> Self {
+        Self {
+            color,
+            fade: Duration::from_millis(500).as_secs_f32(),
+            weight: 0.,
+            landed: Arc::new(AtomicBool::new(false)),
+        }
+    }
+    pub fn fade(mut self, fade: Duration) -> Self {
+        self.fade = fade.as_secs_f32();
+        self
+    }
+    pub fn weight(&self) -> f32 {
+        self.weight
+    }
+    pub fn flash(&mut self) {
+        self.weight = 1.;
+    }
+    pub fn try_watch<P>(&self, session: &Session<P>) -> Result<(), PerformError>
+    where
+        P: StoreProvider,
+        P::Value: Clone,
+    {
+        session.try_on_complete(self.hook())
+    }
+    pub async fn watch<P>(&self, session: &Session<P>)
+    where
+        P: StoreProvider,
+        P::Value: Clone,
+    {
+        session.on_complete(self.hook()).await
+    }
+    pub fn styling(&mut self, ui: &mut egui::Ui) -> bool {
+        if self.landed.swap(false, Ordering::AcqRel) {
+            self.flash();
+        }
+        if self.weight <= 0. {
+            return false;
+        }
+        let visuals = ui.visuals_mut();
+        visuals.extreme_bg_color = blend(visuals.extreme_bg_color, self.color, self.weight);
+        self.step(ui.input().stable_dt);
+        ui.ctx().request_repaint();
+        true
+    }
+    fn hook<T>(&self) -> impl FnMut(&Result<T, PerformError>) + Send + 'static {
+        let landed = self.landed.clone();
+        move |result| {
+            if result.is_ok() {
+                landed.store(true, Ordering::Release);
+            }
+        }
+    }
+    fn step(&mut self, dt: f32) {
+        self.weight = match self.fade > 0. {
+            true => (self.weight - dt / self.fade).max(0.),
+            false => 0.,
+        };
+    }
+}
+
+fn blend(base: egui::Color32, highlight: egui::Color32, weight: f32) -> egui::Color32 {
+    let mix = |base: u8, highlight: u8| {
+        (base as f32 + (highlight as f32 - base as f32) * weight).round() as u8
+    };
+    egui::Color32::from_rgb(
+        mix(base.r(), highlight.r()),
+        mix(base.g(), highlight.g()),
+        mix(base.b(), highlight.b()),
+    )
+}
+
+pub fn show_when_ready<P, R>(
+    ui: &mut egui::Ui,
+    performer: &mut Performer<P>,
+    cache: &mut Option<P::Value>,
+    add: impl FnOnce(&mut egui::Ui, &P::Value) -> R,
+) -> Option<R>
+where
+    P: StoreProvider,
+{
+    if let Ok(value) = performer.try_take() {
+        *cache = Some(value);
+    }
+    if let Some(value) = cache.as_ref() {
+        return Some(add(ui, value));
+    }
+    placeholder(ui, performer);
+    None
+}
+
+impl<P: StoreProvider> Performer<P> {
+    pub fn ui<R>(
+        &mut self,
+        ui: &mut egui::Ui,
+        add: impl FnOnce(&mut egui::Ui, &P::Value) -> R,
+    ) -> Option<R> {
+        self.poll();
+        if let Some(value) = self.cached() {
+            return Some(add(ui, value));
+        }
+        placeholder(ui, self);
+        None
+    }
+}
+
+fn placeholder<P: StoreProvider>(ui: &mut egui::Ui, performer: &Performer<P>) {
+    match performer.state().error() {
+        Some(error) => ui.colored_label(ui.visuals().error_fg_color, error.to_string()),
+        None => pending_label(ui, "", performer.is_pending()),
+    };
+}
+
+pub fn paged_list<T, P, F, Fut>(
+    ui: &mut egui::Ui,
+    loader: &mut PagedLoader<T, P>,
+    row_height: f32,
+    factory: F,
+    add_row: impl FnMut(&mut egui::Ui, &T),
+) where
+    P: StoreProvider<Value = Page<T>>,
+    F: FnOnce(usize) -> Fut,
+    Fut: Future<Output = Page<T>> + 'static + MaybeSend,
+{
+    if show_paged(ui, loader, row_height, add_row) {
+        loader.load_next_page(factory);
+    }
+}
+
+fn show_paged<T, P>(
+    ui: &mut egui::Ui,
+    loader: &mut PagedLoader<T, P>,
+    row_height: f32,
+    mut add_row: impl FnMut(&mut egui::Ui, &T),
+) -> bool
+where
+    P: StoreProvider<Value = Page<T>>,
+{
+    loader.poll();
+    let footer = loader.has_more() || loader.error().is_some();
+    let rows = loader.len() + usize::from(footer);
+    let mut last_visible = None;
+    egui::ScrollArea::vertical().show_rows(ui, row_height, rows, |ui, range| {
+        last_visible = range.end.checked_sub(1);
+        for row in range {
+            match loader.items().get(row) {
+                Some(item) => add_row(ui, item),
+                None => match loader.error() {
+                    Some(error) => {
+                        ui.colored_label(ui.visuals().error_fg_color, error.to_string());
+                    }
+                    None => {
+                        pending_label(ui, "", loader.is_loading());
+                    }
+                },
+            }
+        }
+    });
+    loader.error().is_none()
+        && (loader.is_empty() || last_visible.is_some_and(|last| loader.near_end(last, 0)))
+}
+
+pub fn progress_bar<P: StoreProvider>(
+    ui: &mut egui::Ui,
+    performer: &Performer<P>,
+) -> egui::Response {
+    let bar = match performer.progress() {
+        Some(fraction) => egui::ProgressBar::new(fraction).show_percentage(),
+        None => egui::ProgressBar::new(0.0).animate(performer.is_pending()),
+    };
+    ui.add(bar)
+}
+
+fn pending_label(ui: &mut egui::Ui, text: &str, pending: bool) -> egui::Response {
+    match pending {
+        true => {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label(text);
+            })
+            .response
+        }
+        false => ui.label(text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(not(target_arch = "wasm32"))]
+    use super::{
+        paged_list, show_when_ready, AsyncButton, AsyncLabel, AsyncTextField, FreshnessHighlight,
+    };
+    use super::{ElapsedSpinner, RepaintDriver};
+    #[cfg(not(target_arch = "wasm32"))]
+    use crate::Perform;
+    use std::time::Duration;
+
+    mod labelled {
+        crate::build_perform!(u32);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn frame(ctx: &egui::Context, add: impl FnOnce(&mut egui::Ui)) {
+        let _ = ctx.run(egui::RawInput::default(), |ctx| {
+            egui::CentralPanel::default().show(ctx, add);
+        });
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn widgets_keep_the_value_once_it_arrives() {
+        let ctx = egui::Context::default();
+        let mut label = AsyncLabel::new(
+            labelled::Performer::new(labelled::Session::activate().await),
+            "loading",
+        );
+        let mut field = AsyncTextField::new(
+            labelled::Performer::new(labelled::Session::activate().await),
+            "loading",
+        );
+        label
+            .performer_mut()
+            .perform_one_time_or_not(async { 4 })
+            .await;
+        field
+            .performer_mut()
+            .perform_one_time_or_not(async { 2 })
+            .await;
+        for _ in 0..2 {
+            frame(&ctx, |ui| {
+                label.ui(ui);
+                field.ui(ui);
+            });
+        }
+        assert_eq!(label.value(), Some(&4));
+        assert_eq!(field.text(), "2");
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn async_button_hands_the_result_over_once() {
+        let ctx = egui::Context::default();
+        let mut button = AsyncButton::new(
+            labelled::Performer::new(labelled::Session::activate().await),
+            "Save",
+        );
+        button.performer_mut().perform_latest_spawned(async { 9 });
+        let mut results = Vec::new();
+        for _ in 0..3 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            frame(&ctx, |ui| {
+                button.ui(ui, || async { 0 }, |result| results.push(result.unwrap()));
+            });
+        }
+        assert_eq!(results, vec![9]);
+        assert!(!button.performer_mut().is_pending());
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn show_when_ready_keeps_showing_the_cached_value() {
+        let ctx = egui::Context::default();
+        let mut performer = labelled::Performer::new(labelled::Session::activate().await);
+        let mut cache = None;
+        let mut shown = Vec::new();
+        frame(&ctx, |ui| {
+            let shown = show_when_ready(ui, &mut performer, &mut cache, |_, value| *value);
+            assert_eq!(shown, None);
+        });
+        performer.perform_latest_spawned(async { 4 });
+        for _ in 0..3 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            frame(&ctx, |ui| {
+                shown.extend(show_when_ready(
+                    ui,
+                    &mut performer,
+                    &mut cache,
+                    |_, value| *value,
+                ));
+            });
+        }
+        assert_eq!(shown, vec![4, 4, 4]);
+        assert_eq!(cache, Some(4));
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn performer_ui_triggers_once_and_renders_the_cached_value() {
+        let ctx = egui::Context::default();
+        let mut performer = labelled::Performer::new(labelled::Session::activate().await);
+        let runs = std::sync::Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let counter = runs.clone();
+        performer.set_factory(move || {
+            let run = counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            async move { 10 + run }
+        });
+        let mut shown = Vec::new();
+        for _ in 0..4 {
+            frame(&ctx, |ui| shown.extend(performer.ui(ui, |_, value| *value)));
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert_eq!(shown.first(), Some(&10));
+        assert!(shown.iter().all(|value| *value == 10));
+        assert_eq!(runs.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    mod paged {
+        crate::build_perform!(crate::Page<u32>);
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn paged_list_loads_pages_while_the_end_is_visible() {
+        let ctx = egui::Context::default();
+        let mut loader = crate::PagedLoader::<u32, paged::Provider>::new();
+        let fetch = |page: usize| async move { crate::Page::new(vec![page as u32], page < 2) };
+        for _ in 0..10 {
+            frame(&ctx, |ui| {
+                paged_list(ui, &mut loader, 18., fetch, |ui, item| {
+                    ui.label(item.to_string());
+                })
+            });
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert_eq!(loader.items(), &[0, 1, 2]);
+        assert!(!loader.has_more());
+    }
+
+    #[test]
+    fn elapsed_spinner_switches_message_past_the_threshold() {
+        let spinner = ElapsedSpinner::new(Duration::from_secs(3)).slow_text("still working");
+        assert_eq!(spinner.caption(Duration::from_millis(1500)), "1.5s");
+        assert_eq!(
+            spinner.caption(Duration::from_secs(4)),
+            "still working (4s)"
+        );
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn freshness_highlight_flashes_when_a_value_lands_and_fades_out() {
+        let ctx = egui::Context::default();
+        let session = labelled::Session::activate().await;
+        let mut highlight = FreshnessHighlight::default().fade(Duration::from_millis(100));
+        highlight.watch(&session).await;
+        let mut styled = false;
+        frame(&ctx, |ui| styled = highlight.styling(ui));
+        assert!(!styled);
+        session.perform(async { 3 }).await;
+        frame(&ctx, |ui| styled = highlight.styling(ui));
+        assert!(styled);
+        assert!(highlight.weight() > 0.);
+        for _ in 0..20 {
+            frame(&ctx, |ui| styled = highlight.styling(ui));
+        }
+        assert!(!styled);
+        assert_eq!(highlight.weight(), 0.);
+    }
+
+    #[test]
+    fn drive_backs_off_while_pending_and_stops_when_idle() {
+        let ctx = egui::Context::default();
+        let mut driver = RepaintDriver::new(Duration::from_millis(10), Duration::from_millis(30));
+        assert!(driver.drive(&ctx, [false, true]));
+        assert_eq!(driver.interval(), Some(Duration::from_millis(10)));
+        driver.drive(&ctx, [true]);
+        driver.drive(&ctx, [true]);
+        assert_eq!(driver.interval(), Some(Duration::from_millis(30)));
+        assert!(!driver.drive(&ctx, [false, false]));
+        assert!(driver.is_idle());
+        driver.drive(&ctx, [true]);
+        assert_eq!(driver.interval(), Some(Duration::from_millis(10)));
+    }
+}
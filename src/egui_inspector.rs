@@ -0,0 +1,86 @@
+use crate::inspect::{self, StoreSnapshot};
+
+pub fn show(ctx: &egui::Context) {
+    egui::Window::new("perform_wasm")
+        .default_width(360.)
+        .show(ctx, |ui| stores(ui, &inspect::snapshots()));
+}
+
+fn stores(ui: &mut egui::Ui, snapshots: &[StoreSnapshot]) {
+    if snapshots.is_empty() {
+        ui.label("no stores activated yet");
+        return;
+    }
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        for snapshot in snapshots {
+            store(ui, snapshot);
+        }
+    });
+}
+
+fn store(ui: &mut egui::Ui, snapshot: &StoreSnapshot) {
+    let title = format!(
+        "{}  pending {} / ready {} / in flight {} / queued {}",
+        short_name(snapshot.name),
+        snapshot.pending(),
+        snapshot.ready(),
+        snapshot.in_flight,
+        snapshot.queued,
+    );
+    egui::CollapsingHeader::new(title)
+        .id_source(snapshot.name)
+        .show(ui, |ui| {
+            if snapshot.locked {
+                ui.weak("store is locked, entries unavailable this frame");
+            }
+            if let Some(error) = &snapshot.last_error {
+                ui.colored_label(
+                    ui.visuals().error_fg_color,
+                    format!("last error: {}", error),
+                );
+            }
+            egui::Grid::new(snapshot.name).striped(true).show(ui, |ui| {
+                ui.strong("task");
+                ui.strong("state");
+                ui.strong("age");
+                ui.end_row();
+                for entry in &snapshot.entries {
+                    ui.monospace(&entry.id.to_string()[..8]);
+                    match (&entry.error, entry.ready) {
+                        (Some(error), _) => {
+                            ui.colored_label(ui.visuals().error_fg_color, error.to_string())
+                        }
+                        (None, true) => ui.label("ready"),
+                        (None, false) => ui.label("pending"),
+                    };
+                    match entry.age {
+                        Some(age) => ui.label(format!("{:.1}s", age.as_secs_f32())),
+                        None => ui.label("-"),
+                    };
+                    ui.end_row();
+                }
+            });
+        });
+}
+
+fn short_name(name: &'static str) -> &'static str {
+    let name = name.strip_suffix("::Provider").unwrap_or(name);
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{short_name, show};
+
+    #[test]
+    fn store_names_drop_the_module_path_and_provider() {
+        assert_eq!(short_name("app::remote::ip::Provider"), "ip");
+        assert_eq!(short_name("Custom"), "Custom");
+    }
+
+    #[test]
+    fn inspector_renders_without_any_store() {
+        let ctx = egui::Context::default();
+        let _ = ctx.run(egui::RawInput::default(), show);
+    }
+}
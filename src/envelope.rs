@@ -0,0 +1,142 @@
+use crate::{PerformError, RemoteError, Uuid};
+use serde::{Deserialize, Serialize};
+
+pub const TASK_MESSAGE_VERSION: u16 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerformEnvelope<T> {
+    pub id: Uuid,
+    pub result: Result<T, PerformError>,
+}
+
+impl<T> PerformEnvelope<T> {
+    pub fn new(id: Uuid, result: Result<T, PerformError>) -> Self {
+        Self { id, result }
+    }
+    pub fn into_result(self) -> Result<T, PerformError> {
+        self.result
+    }
+}
+
+impl<T> From<PerformEnvelope<T>> for (Uuid, Result<T, PerformError>) {
+    fn from(envelope: PerformEnvelope<T>) -> Self {
+        (envelope.id, envelope.result)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskMessage<T> {
+    pub version: u16,
+    pub task_id: u64,
+    pub store_id: String,
+    #[serde(default)]
+    pub task: String,
+    pub payload: Option<T>,
+    pub error: Option<PerformError>,
+}
+
+impl<T> TaskMessage<T> {
+    pub fn new(task_id: u64, store_id: impl Into<String>, result: Result<T, PerformError>) -> Self {
+        let (payload, error) = match result {
+            Ok(payload) => (Some(payload), None),
+            Err(error) => (None, Some(error)),
+        };
+        Self {
+            version: TASK_MESSAGE_VERSION,
+            task_id,
+            store_id: store_id.into(),
+            task: String::new(),
+            payload,
+            error,
+        }
+    }
+
+    pub fn for_task(
+        task_id: u64,
+        task: impl Into<String>,
+        result: Result<T, PerformError>,
+    ) -> Self {
+        Self {
+            task: task.into(),
+            ..Self::new(task_id, String::new(), result)
+        }
+    }
+
+    pub fn is_supported(&self) -> bool {
+        self.version == TASK_MESSAGE_VERSION
+    }
+
+    pub fn into_result(self) -> Result<T, PerformError> {
+        if !self.is_supported() {
+            return Err(PerformError::task(RemoteError {
+                message: format!("unsupported task message version: {}", self.version),
+            }));
+        }
+        match (self.payload, self.error) {
+            (_, Some(error)) => Err(error),
+            (Some(payload), None) => Ok(payload),
+            (None, None) => Err(PerformError::Empty),
+        }
+    }
+
+    #[cfg(all(target_arch = "wasm32", feature = "js-serde"))]
+    pub fn to_js(&self) -> Result<wasm_bindgen::JsValue, PerformError>
+    where
+        T: Serialize,
+    {
+        serde_wasm_bindgen::to_value(self)
+            .map_err(|error| wasm_bindgen::JsValue::from(error).into())
+    }
+
+    #[cfg(all(target_arch = "wasm32", feature = "js-serde"))]
+    pub fn from_js(value: wasm_bindgen::JsValue) -> Result<Self, PerformError>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        serde_wasm_bindgen::from_value(value)
+            .map_err(|error| wasm_bindgen::JsValue::from(error).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PerformEnvelope, TaskMessage, TASK_MESSAGE_VERSION};
+    use crate::{PerformError, Uuid};
+
+    #[test]
+    fn envelope_round_trip() {
+        let id = Uuid::new_v4();
+        let json = serde_json::to_string(&PerformEnvelope::new(id, Ok(42_u32))).unwrap();
+        let envelope: PerformEnvelope<u32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(envelope.id, id);
+        assert_eq!(envelope.into_result().unwrap(), 42);
+
+        let json =
+            serde_json::to_string(&PerformEnvelope::<u32>::new(id, Err(PerformError::Empty)))
+                .unwrap();
+        let envelope: PerformEnvelope<u32> = serde_json::from_str(&json).unwrap();
+        assert!(matches!(envelope.result, Err(PerformError::Empty)));
+    }
+
+    #[test]
+    fn task_message_carries_payload_or_error() {
+        let json = serde_json::to_string(&TaskMessage::new(3, "images", Ok(9_u32))).unwrap();
+        let message: TaskMessage<u32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(message.version, TASK_MESSAGE_VERSION);
+        assert_eq!((message.task_id, message.store_id.as_str()), (3, "images"));
+        assert!(message.task.is_empty());
+        assert_eq!(message.into_result().unwrap(), 9);
+
+        let failed = TaskMessage::<u32>::new(4, "images", Err(PerformError::Busy));
+        assert!(matches!(failed.into_result(), Err(PerformError::Busy)));
+
+        let missing: TaskMessage<u32> =
+            serde_json::from_str(r#"{"version":1,"task_id":5,"store_id":"images"}"#).unwrap();
+        assert!(matches!(missing.into_result(), Err(PerformError::Empty)));
+
+        let mut future = TaskMessage::new(6, "images", Ok(1_u32));
+        future.version += 1;
+        assert!(!future.is_supported());
+        assert!(future.into_result().is_err());
+    }
+}
@@ -0,0 +1,167 @@
+use crate::Error;
+use std::sync::Arc;
+
+#[derive(Debug, Error, Clone)]
+pub enum PerformError {
+    #[error("Locked")]
+    Locked,
+    #[error("Empty")]
+    Empty,
+    #[error("Store is full")]
+    StoreFull,
+    #[error("Expired")]
+    Expired,
+    #[error("Timed out")]
+    TimedOut,
+    #[error("Superseded")]
+    Superseded,
+    #[error("Cancelled")]
+    Cancelled,
+    #[error("Circuit open")]
+    CircuitOpen,
+    #[error("Busy")]
+    Busy,
+    #[error("Store is closed")]
+    Closed,
+    #[error("Task failed: {0}")]
+    Task(#[source] Arc<dyn std::error::Error + Send + Sync>),
+}
+
+impl PerformError {
+    pub fn task<E>(error: E) -> Self
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        Self::Task(Arc::new(error))
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl From<reqwest::Error> for PerformError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::task(error)
+    }
+}
+
+#[cfg(feature = "gloo-net")]
+impl From<gloo_net::Error> for PerformError {
+    fn from(error: gloo_net::Error) -> Self {
+        Self::task(error)
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", feature = "web-sys"))]
+impl From<wasm_bindgen::JsValue> for PerformError {
+    fn from(value: wasm_bindgen::JsValue) -> Self {
+        use wasm_bindgen::JsCast;
+        let message = match value.dyn_ref::<js_sys::Error>() {
+            Some(error) => String::from(error.message()),
+            None => value.as_string().unwrap_or_else(|| format!("{value:?}")),
+        };
+        Self::task(RemoteError { message })
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", feature = "web-sys"))]
+impl From<PerformError> for wasm_bindgen::JsValue {
+    fn from(error: PerformError) -> Self {
+        js_sys::Error::new(&error.to_string()).into()
+    }
+}
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[error("{message}")]
+pub struct RemoteError {
+    pub message: String,
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::{PerformError, RemoteError};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::sync::Arc;
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(tag = "kind", content = "message")]
+    enum Repr {
+        Locked,
+        Empty,
+        StoreFull,
+        Expired,
+        TimedOut,
+        Superseded,
+        Cancelled,
+        CircuitOpen,
+        Busy,
+        Closed,
+        Task(String),
+    }
+
+    impl Serialize for PerformError {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let repr = match self {
+                PerformError::Locked => Repr::Locked,
+                PerformError::Empty => Repr::Empty,
+                PerformError::StoreFull => Repr::StoreFull,
+                PerformError::Expired => Repr::Expired,
+                PerformError::TimedOut => Repr::TimedOut,
+                PerformError::Superseded => Repr::Superseded,
+                PerformError::Cancelled => Repr::Cancelled,
+                PerformError::CircuitOpen => Repr::CircuitOpen,
+                PerformError::Busy => Repr::Busy,
+                PerformError::Closed => Repr::Closed,
+                PerformError::Task(error) => Repr::Task(error.to_string()),
+            };
+            repr.serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for PerformError {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let error = match Repr::deserialize(deserializer)? {
+                Repr::Locked => PerformError::Locked,
+                Repr::Empty => PerformError::Empty,
+                Repr::StoreFull => PerformError::StoreFull,
+                Repr::Expired => PerformError::Expired,
+                Repr::TimedOut => PerformError::TimedOut,
+                Repr::Superseded => PerformError::Superseded,
+                Repr::Cancelled => PerformError::Cancelled,
+                Repr::CircuitOpen => PerformError::CircuitOpen,
+                Repr::Busy => PerformError::Busy,
+                Repr::Closed => PerformError::Closed,
+                Repr::Task(message) => PerformError::Task(Arc::new(RemoteError { message })),
+            };
+            Ok(error)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PerformError;
+    use std::error::Error as _;
+
+    #[derive(Debug, crate::Error)]
+    #[error("connection reset")]
+    struct Cause;
+
+    #[test]
+    fn task_exposes_source() {
+        let error = PerformError::task(Cause);
+        assert_eq!(error.to_string(), "Task failed: connection reset");
+        assert_eq!(error.source().unwrap().to_string(), "connection reset");
+        assert!(PerformError::Empty.source().is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_round_trip_keeps_message() {
+        let json = serde_json::to_string(&PerformError::task(Cause)).unwrap();
+        assert_eq!(json, r#"{"kind":"Task","message":"connection reset"}"#);
+        let error: PerformError = serde_json::from_str(&json).unwrap();
+        assert_eq!(error.to_string(), "Task failed: connection reset");
+
+        let error: PerformError = serde_json::from_str(r#"{"kind":"Locked"}"#).unwrap();
+        assert!(matches!(error, PerformError::Locked));
+    }
+}
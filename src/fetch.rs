@@ -0,0 +1,97 @@
+use crate::js::resolve;
+use crate::{CancellationToken, PerformError, PerformHandle, Session, StoreProvider};
+use futures::future::{self, Either};
+use std::cell::Cell;
+use std::future::Future;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{AbortController, AbortSignal, RequestInit, Response};
+
+pub struct AbortGuard {
+    controller: AbortController,
+    armed: Cell<bool>,
+}
+
+impl AbortGuard {
+    pub fn new() -> Result<Self, PerformError> {
+        Ok(Self {
+            controller: AbortController::new()?,
+            armed: Cell::new(true),
+        })
+    }
+
+    pub fn controller(&self) -> &AbortController {
+        &self.controller
+    }
+    pub fn signal(&self) -> AbortSignal {
+        self.controller.signal()
+    }
+
+    pub fn abort(&self) {
+        self.armed.set(false);
+        self.controller.abort();
+    }
+    pub fn disarm(&self) {
+        self.armed.set(false);
+    }
+}
+
+impl Drop for AbortGuard {
+    fn drop(&mut self) {
+        if self.armed.get() {
+            self.controller.abort();
+        }
+    }
+}
+
+fn start(url: &str, init: &RequestInit) -> Result<js_sys::Promise, PerformError> {
+    let global = js_sys::global();
+    let fetch = js_sys::Reflect::get(&global, &JsValue::from_str("fetch"))?
+        .dyn_into::<js_sys::Function>()?;
+    Ok(fetch
+        .call2(&global, &JsValue::from_str(url), init)?
+        .dyn_into::<js_sys::Promise>()?)
+}
+
+pub async fn fetch_with<T, F, Fut>(
+    url: &str,
+    init: RequestInit,
+    guard: &AbortGuard,
+    token: &CancellationToken,
+    read: F,
+) -> Result<T, PerformError>
+where
+    F: FnOnce(Response) -> Fut,
+    Fut: Future<Output = Result<T, PerformError>>,
+{
+    init.set_signal(Some(&guard.signal()));
+    let request = async {
+        let response = resolve(start(url, &init)?).await?;
+        read(response.dyn_into::<Response>()?).await
+    };
+    let cancelled = token.cancelled();
+    futures::pin_mut!(request, cancelled);
+    match future::select(request, cancelled).await {
+        Either::Left((result, _)) => {
+            guard.disarm();
+            result
+        }
+        Either::Right(_) => {
+            guard.abort();
+            Err(PerformError::Cancelled)
+        }
+    }
+}
+
+impl<P: StoreProvider> Session<P> {
+    pub fn perform_fetch<F, Fut>(&self, url: &str, init: RequestInit, read: F) -> PerformHandle
+    where
+        F: FnOnce(Response) -> Fut + 'static,
+        Fut: Future<Output = Result<P::Value, PerformError>> + 'static,
+    {
+        let (url, token) = (url.to_string(), self.cancellation_token());
+        self.perform_result_with_spawn_local(async move {
+            let guard = AbortGuard::new()?;
+            fetch_with(&url, init, &guard, &token, read).await
+        })
+    }
+}
@@ -0,0 +1,60 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+
+type Callback = Closure<dyn FnMut(f64)>;
+
+struct Frame {
+    window: web_sys::Window,
+    handle: Cell<Option<i32>>,
+    callback: RefCell<Option<Callback>>,
+}
+
+impl Frame {
+    fn request(&self) {
+        if let Some(callback) = self.callback.borrow().as_ref() {
+            let handle = self
+                .window
+                .request_animation_frame(callback.as_ref().unchecked_ref());
+            self.handle.set(handle.ok());
+        }
+    }
+}
+
+pub struct FrameLoop {
+    frame: Rc<Frame>,
+}
+
+impl FrameLoop {
+    pub fn stop(self) {}
+}
+
+impl Drop for FrameLoop {
+    fn drop(&mut self) {
+        if let Some(handle) = self.frame.handle.take() {
+            let _ = self.frame.window.cancel_animation_frame(handle);
+        }
+    }
+}
+
+pub fn on_animation_frame<F>(mut callback: F) -> Option<FrameLoop>
+where
+    F: FnMut(f64) + 'static,
+{
+    let frame = Rc::new(Frame {
+        window: web_sys::window()?,
+        handle: Cell::new(None),
+        callback: RefCell::new(None),
+    });
+    let weak = Rc::downgrade(&frame);
+    *frame.callback.borrow_mut() = Some(Closure::new(move |timestamp: f64| {
+        crate::pump();
+        callback(timestamp);
+        if let Some(frame) = weak.upgrade() {
+            frame.request();
+        }
+    }));
+    frame.request();
+    Some(FrameLoop { frame })
+}
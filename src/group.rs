@@ -0,0 +1,137 @@
+use crate::store::{lock_and_do_mut, try_lock_and_do_mut};
+use crate::{ok_or_empty, Error, Perform, PerformError, Session, Store, StoreProvider, Uuid};
+
+#[derive(Debug, Error, Clone)]
+#[error("{} of {total} tasks failed", failures.len())]
+pub struct GroupError {
+    pub total: usize,
+    pub failures: Vec<(Uuid, PerformError)>,
+}
+
+pub struct SessionGroup<P> {
+    sessions: Vec<Session<P>>,
+}
+
+impl<P> Default for SessionGroup<P> {
+    fn default() -> Self {
+        Self {
+            sessions: Vec::new(),
+        }
+    }
+}
+
+impl<P> SessionGroup<P> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, session: Session<P>) -> &Session<P> {
+        self.sessions.push(session);
+        self.sessions.last().unwrap()
+    }
+    pub fn sessions(&self) -> &[Session<P>] {
+        &self.sessions
+    }
+    pub fn len(&self) -> usize {
+        self.sessions.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+
+    pub fn cancel_all(&self) {
+        for session in &self.sessions {
+            session.cancel();
+        }
+    }
+}
+
+impl<P: StoreProvider> SessionGroup<P> {
+    pub fn all_ready(&self) -> bool {
+        P::store()
+            .try_lock()
+            .is_ok_and(|store| self.ids().all(|id| store.is_ready(id)))
+    }
+
+    pub fn try_take_all(&self) -> Result<Vec<P::Value>, GroupError> {
+        try_lock_and_do_mut(P::shared(), |store| Ok(self.take_all_from(store)))
+            .unwrap_or_else(|error| Err(self.failed_with(error)))
+    }
+    pub async fn take_all(&self) -> Result<Vec<P::Value>, GroupError> {
+        lock_and_do_mut(P::shared(), |store| self.take_all_from(store)).await
+    }
+
+    fn ids(&self) -> impl Iterator<Item = &Uuid> {
+        self.sessions.iter().map(|session| session.id())
+    }
+    fn take_all_from(&self, store: &mut Store<P::Value>) -> Result<Vec<P::Value>, GroupError> {
+        let pending: Vec<_> = self
+            .ids()
+            .filter(|id| !store.is_ready(id))
+            .map(|id| (*id, PerformError::Empty))
+            .collect();
+        if !pending.is_empty() {
+            return Err(GroupError {
+                total: self.len(),
+                failures: pending,
+            });
+        }
+        let mut values = Vec::with_capacity(self.len());
+        let mut failures = Vec::new();
+        for id in self.ids() {
+            match ok_or_empty(store.remove(id)) {
+                Ok(value) => values.push(value),
+                Err(error) => failures.push((*id, error)),
+            }
+        }
+        match failures.is_empty() {
+            true => Ok(values),
+            false => Err(GroupError {
+                total: self.len(),
+                failures,
+            }),
+        }
+    }
+    fn failed_with(&self, error: PerformError) -> GroupError {
+        GroupError {
+            total: self.len(),
+            failures: self.ids().map(|id| (*id, error.clone())).collect(),
+        }
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::SessionGroup;
+    use crate::{Perform, PerformError};
+
+    mod grouped {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    async fn take_all_waits_for_every_session_and_aggregates_errors() {
+        let mut group = SessionGroup::new();
+        for _ in 0..3 {
+            group.push(grouped::Session::activate().await);
+        }
+        for (value, session) in group.sessions()[..2].iter().enumerate() {
+            session.perform(async move { value as u32 }).await;
+        }
+        assert!(!group.all_ready());
+        let error = group.take_all().await.unwrap_err();
+        assert_eq!(error.failures.len(), 1);
+
+        group.sessions()[2]
+            .perform_result(async { Err(PerformError::TimedOut) })
+            .await;
+        assert!(group.all_ready());
+        let error = group.take_all().await.unwrap_err();
+        assert_eq!(error.to_string(), "1 of 3 tasks failed");
+
+        for session in group.sessions() {
+            session.perform(async { 1 }).await;
+        }
+        assert_eq!(group.try_take_all().unwrap(), vec![1, 1, 1]);
+    }
+}
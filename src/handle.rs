@@ -0,0 +1,76 @@
+use futures::future::{AbortHandle, BoxFuture, FutureExt};
+use std::future::IntoFuture;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::Notify;
+
+#[derive(Default)]
+struct Inner {
+    finished: AtomicBool,
+    notify: Notify,
+}
+
+#[derive(Clone)]
+pub struct PerformHandle {
+    abort: AbortHandle,
+    inner: Arc<Inner>,
+}
+
+impl PerformHandle {
+    pub(crate) fn new(abort: AbortHandle) -> (Self, Finish) {
+        let inner = Arc::new(Inner::default());
+        (
+            Self {
+                abort,
+                inner: inner.clone(),
+            },
+            Finish(inner),
+        )
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.inner.finished.load(Ordering::SeqCst)
+    }
+    pub fn is_aborted(&self) -> bool {
+        self.abort.is_aborted()
+    }
+    pub fn abort(&self) {
+        self.abort.abort()
+    }
+    pub async fn join(&self) {
+        loop {
+            let notified = self.inner.notify.notified();
+            if self.is_finished() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+impl IntoFuture for PerformHandle {
+    type Output = ();
+    type IntoFuture = BoxFuture<'static, ()>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        async move { self.join().await }.boxed()
+    }
+}
+
+impl std::fmt::Debug for PerformHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PerformHandle")
+            .field("finished", &self.is_finished())
+            .field("aborted", &self.is_aborted())
+            .finish()
+    }
+}
+
+pub(crate) struct Finish(Arc<Inner>);
+
+impl Drop for Finish {
+    fn drop(&mut self) {
+        self.0.finished.store(true, Ordering::SeqCst);
+        self.0.notify.notify_waiters();
+    }
+}
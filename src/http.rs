@@ -0,0 +1,194 @@
+use crate::{PerformError, RemoteError, RetryPolicy, Session, StoreProvider};
+#[cfg(feature = "serde")]
+use serde::{de::DeserializeOwned, Serialize};
+
+fn status_error(status: u16, url: &str) -> PerformError {
+    PerformError::task(RemoteError {
+        message: format!("HTTP {status} from {url}"),
+    })
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn text(response: gloo_net::http::Response, url: &str) -> Result<String, PerformError> {
+    if !response.ok() {
+        return Err(status_error(response.status(), url));
+    }
+    Ok(response.text().await?)
+}
+#[cfg(not(target_arch = "wasm32"))]
+async fn text(response: reqwest::Response, url: &str) -> Result<String, PerformError> {
+    if !response.status().is_success() {
+        return Err(status_error(response.status().as_u16(), url));
+    }
+    Ok(response.text().await?)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub async fn fetch_text(url: &str) -> Result<String, PerformError> {
+    text(gloo_net::http::Request::get(url).send().await?, url).await
+}
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn fetch_text(url: &str) -> Result<String, PerformError> {
+    text(reqwest::get(url).await?, url).await
+}
+
+#[cfg(all(target_arch = "wasm32", feature = "serde"))]
+async fn post_text(url: &str, body: String) -> Result<String, PerformError> {
+    let request = gloo_net::http::Request::post(url)
+        .header("Content-Type", "application/json")
+        .body(body)?;
+    text(request.send().await?, url).await
+}
+#[cfg(all(not(target_arch = "wasm32"), feature = "serde"))]
+async fn post_text(url: &str, body: String) -> Result<String, PerformError> {
+    let request = reqwest::Client::new()
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(body);
+    text(request.send().await?, url).await
+}
+
+#[cfg(feature = "serde")]
+pub async fn fetch_json<T: DeserializeOwned>(url: &str) -> Result<T, PerformError> {
+    let text = fetch_text(url).await?;
+    serde_json::from_str(&text).map_err(PerformError::task)
+}
+
+#[cfg(feature = "serde")]
+pub async fn post_json<B, T>(url: &str, body: &B) -> Result<T, PerformError>
+where
+    B: Serialize + ?Sized,
+    T: DeserializeOwned,
+{
+    let body = serde_json::to_string(body).map_err(PerformError::task)?;
+    let text = post_text(url, body).await?;
+    serde_json::from_str(&text).map_err(PerformError::task)
+}
+
+impl<P: StoreProvider<Value = String>> Session<P> {
+    pub async fn perform_fetch_text(&self, url: &str, policy: RetryPolicy) {
+        let url = url.to_string();
+        self.perform_with_retry(policy, move || {
+            let url = url.clone();
+            async move { fetch_text(&url).await }
+        })
+        .await
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<P: StoreProvider> Session<P>
+where
+    P::Value: DeserializeOwned,
+{
+    pub async fn perform_fetch_json(&self, url: &str, policy: RetryPolicy) {
+        let url = url.to_string();
+        self.perform_with_retry(policy, move || {
+            let url = url.clone();
+            async move { fetch_json(&url).await }
+        })
+        .await
+    }
+
+    pub async fn perform_post_json<B: Serialize + 'static>(
+        &self,
+        url: &str,
+        body: B,
+        policy: RetryPolicy,
+    ) {
+        let (url, body) = (url.to_string(), std::rc::Rc::new(body));
+        self.perform_with_retry(policy, move || {
+            let (url, body) = (url.clone(), body.clone());
+            async move { post_json(&url, &*body).await }
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(not(target_arch = "wasm32"))]
+    use crate::{Backoff, Perform, RetryPolicy};
+    #[cfg(not(target_arch = "wasm32"))]
+    use std::time::Duration;
+
+    #[test]
+    fn status_errors_name_the_url() {
+        let error = super::status_error(404, "http://example.com/missing");
+        assert_eq!(
+            error.to_string(),
+            "Task failed: HTTP 404 from http://example.com/missing"
+        );
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn serve(responses: Vec<&'static str>) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            for (status, body) in responses.into_iter().map(|line| line.split_at(3)) {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = [0; 4096];
+                let _ = stream.read(&mut request).await;
+                let response = format!(
+                    "HTTP/1.1 {status} X\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        format!("http://{address}")
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    mod pages {
+        crate::build_perform!(String);
+    }
+    #[cfg(all(not(target_arch = "wasm32"), feature = "serde"))]
+    mod numbers {
+        crate::build_perform!(Vec<u32>);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn quick_retry(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            backoff: Backoff {
+                initial: Duration::from_millis(1),
+                ..Backoff::default()
+            },
+            jitter: 0.,
+            wait_online: false,
+        }
+    }
+
+    #[tokio::test]
+    #[cfg(all(not(target_arch = "wasm32"), feature = "reqwest"))]
+    async fn fetch_text_retries_failed_statuses() {
+        let url = serve(vec!["503", "200hello"]).await;
+        let session = pages::Session::activate().await;
+        session.perform_fetch_text(&url, quick_retry(2)).await;
+        assert_eq!(session.take().await.unwrap(), "hello");
+
+        let url = serve(vec!["404"]).await;
+        session.perform_fetch_text(&url, quick_retry(1)).await;
+        let failed = session.take().await.unwrap_err().to_string();
+        assert!(failed.contains("HTTP 404"));
+    }
+
+    #[tokio::test]
+    #[cfg(all(not(target_arch = "wasm32"), feature = "reqwest", feature = "serde"))]
+    async fn json_helpers_decode_the_body() {
+        let session = numbers::Session::activate().await;
+        let url = serve(vec!["200[1,2,3]"]).await;
+        session.perform_fetch_json(&url, quick_retry(1)).await;
+        assert_eq!(session.take().await.unwrap(), vec![1, 2, 3]);
+
+        let url = serve(vec!["200[4]"]).await;
+        session
+            .perform_post_json(&url, [4_u32], quick_retry(1))
+            .await;
+        assert_eq!(session.take().await.unwrap(), vec![4]);
+    }
+}
@@ -0,0 +1,214 @@
+use crate::store::{lock_and_do_mut, Completion};
+use crate::{Perform, PerformError, RemoteError, Session, StoreProvider, Uuid};
+use futures::channel::oneshot;
+use futures::future::{AbortHandle, Abortable};
+use futures::StreamExt;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::collections::{HashSet, VecDeque};
+use std::marker::PhantomData;
+use std::rc::Rc;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{IdbDatabase, IdbObjectStore, IdbRequest, IdbTransactionMode};
+
+const OBJECT_STORE: &str = "values";
+
+async fn settled(request: &IdbRequest) -> Result<JsValue, PerformError> {
+    let (sender, receiver) = oneshot::channel();
+    let sender = Rc::new(RefCell::new(Some(sender)));
+    let callback = |succeeded: bool| {
+        let sender = sender.clone();
+        Closure::<dyn FnMut()>::new(move || {
+            if let Some(sender) = sender.borrow_mut().take() {
+                let _ = sender.send(succeeded);
+            }
+        })
+    };
+    let (on_success, on_error) = (callback(true), callback(false));
+    request.set_onsuccess(Some(on_success.as_ref().unchecked_ref()));
+    request.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+    let succeeded = receiver.await.unwrap_or(false);
+    request.set_onsuccess(None);
+    request.set_onerror(None);
+    match succeeded {
+        true => Ok(request.result()?),
+        false => Err(PerformError::task(RemoteError {
+            message: "IndexedDB request failed".to_string(),
+        })),
+    }
+}
+
+struct Hot {
+    capacity: usize,
+    order: VecDeque<Uuid>,
+    attached: HashSet<Uuid>,
+}
+
+pub struct IdbLayer<P: StoreProvider> {
+    db: IdbDatabase,
+    hot: Rc<RefCell<Hot>>,
+    writer: AbortHandle,
+    provider: PhantomData<P>,
+}
+
+impl<P: StoreProvider> IdbLayer<P>
+where
+    P::Value: Serialize + DeserializeOwned + Clone,
+{
+    pub async fn open(name: &str, hot_capacity: usize) -> Result<Self, PerformError> {
+        let factory = web_sys::window()
+            .ok_or(PerformError::Closed)?
+            .indexed_db()?
+            .ok_or(PerformError::Closed)?;
+        let request = factory.open_with_u32(name, 1)?;
+        let upgrade = Closure::<dyn FnMut()>::new({
+            let request = request.clone();
+            move || {
+                let Ok(db) = request.result().and_then(|db| db.dyn_into::<IdbDatabase>()) else {
+                    return;
+                };
+                if !db.object_store_names().contains(OBJECT_STORE) {
+                    let _ = db.create_object_store(OBJECT_STORE);
+                }
+            }
+        });
+        request.set_onupgradeneeded(Some(upgrade.as_ref().unchecked_ref()));
+        let db = settled(&request).await?.dyn_into::<IdbDatabase>()?;
+        request.set_onupgradeneeded(None);
+
+        let hot = Rc::new(RefCell::new(Hot {
+            capacity: hot_capacity,
+            order: VecDeque::new(),
+            attached: HashSet::new(),
+        }));
+        let (writer, registration) = AbortHandle::new_pair();
+        let (target, written) = (db.clone(), hot.clone());
+        let completions = P::shared().completions();
+        crate::spawn_local(async move {
+            let spilled = completions.for_each(move |completion| {
+                let (db, hot) = (target.clone(), written.clone());
+                async move {
+                    let Completion {
+                        id,
+                        result: Ok(value),
+                        ..
+                    } = completion
+                    else {
+                        return;
+                    };
+                    if !hot.borrow().attached.contains(&id) {
+                        return;
+                    }
+                    if Self::put(&db, id, &value).await.is_err() {
+                        return;
+                    }
+                    let evicted = {
+                        let mut hot = hot.borrow_mut();
+                        hot.order.retain(|hot_id| *hot_id != id);
+                        hot.order.push_back(id);
+                        let excess = hot.order.len().saturating_sub(hot.capacity);
+                        hot.order.drain(..excess).collect::<Vec<_>>()
+                    };
+                    if !evicted.is_empty() {
+                        lock_and_do_mut(P::shared(), |store| {
+                            evicted.iter().for_each(|id| drop(store.remove(id)))
+                        })
+                        .await;
+                    }
+                }
+            });
+            let _ = Abortable::new(spilled, registration).await;
+        });
+
+        Ok(Self {
+            db,
+            hot,
+            writer,
+            provider: PhantomData,
+        })
+    }
+
+    fn object_store(
+        db: &IdbDatabase,
+        mode: IdbTransactionMode,
+    ) -> Result<IdbObjectStore, PerformError> {
+        let transaction = db.transaction_with_str_and_mode(OBJECT_STORE, mode)?;
+        Ok(transaction.object_store(OBJECT_STORE)?)
+    }
+
+    fn key(id: Uuid) -> JsValue {
+        JsValue::from_str(&id.to_string())
+    }
+
+    async fn put(db: &IdbDatabase, id: Uuid, value: &P::Value) -> Result<(), PerformError> {
+        let value = serde_wasm_bindgen::to_value(value).map_err(JsValue::from)?;
+        let store = Self::object_store(db, IdbTransactionMode::Readwrite)?;
+        settled(&store.put_with_key(&value, &Self::key(id))?).await?;
+        Ok(())
+    }
+
+    async fn delete(db: &IdbDatabase, id: Uuid) -> Result<(), PerformError> {
+        let store = Self::object_store(db, IdbTransactionMode::Readwrite)?;
+        settled(&store.delete(&Self::key(id))?).await?;
+        Ok(())
+    }
+
+    pub fn attach(&self, session: &Session<P>) {
+        self.hot.borrow_mut().attached.insert(*session.id());
+    }
+
+    pub fn detach(&self, session: &Session<P>) -> bool {
+        let mut hot = self.hot.borrow_mut();
+        hot.order.retain(|id| id != session.id());
+        hot.attached.remove(session.id())
+    }
+
+    pub fn hot_len(&self) -> usize {
+        self.hot.borrow().order.len()
+    }
+
+    fn forget(&self, id: Uuid) {
+        self.hot.borrow_mut().order.retain(|hot_id| *hot_id != id);
+        let db = self.db.clone();
+        crate::spawn_local(async move {
+            let _ = Self::delete(&db, id).await;
+        });
+    }
+
+    pub fn try_take(&self, session: &Session<P>) -> Result<P::Value, PerformError> {
+        let taken = session.try_take();
+        if taken.is_ok() {
+            self.forget(*session.id());
+        }
+        taken
+    }
+
+    pub async fn take(&self, session: &Session<P>) -> Result<P::Value, PerformError> {
+        let id = *session.id();
+        let taken = match session.take().await {
+            Err(PerformError::Empty) => {
+                let store = Self::object_store(&self.db, IdbTransactionMode::Readonly)?;
+                let stored = settled(&store.get(&Self::key(id))?).await?;
+                match stored.is_undefined() {
+                    true => Err(PerformError::Empty),
+                    false => serde_wasm_bindgen::from_value(stored)
+                        .map_err(|error| JsValue::from(error).into()),
+                }
+            }
+            taken => taken,
+        };
+        if taken.is_ok() {
+            self.forget(id);
+        }
+        taken
+    }
+}
+
+impl<P: StoreProvider> Drop for IdbLayer<P> {
+    fn drop(&mut self) {
+        self.writer.abort();
+        self.db.close();
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{PerformError, StoreProvider, Uuid};
+use std::sync::Mutex;
+use std::time::Duration;
+
+type Registered = (&'static str, fn() -> StoreSnapshot);
+
+static REGISTRY: Mutex<Vec<Registered>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone)]
+pub struct EntrySnapshot {
+    pub id: Uuid,
+    pub ready: bool,
+    pub age: Option<Duration>,
+    pub error: Option<PerformError>,
+}
+
+#[derive(Debug, Clone)]
+pub struct StoreSnapshot {
+    pub name: &'static str,
+    pub locked: bool,
+    pub in_flight: usize,
+    pub queued: usize,
+    pub entries: Vec<EntrySnapshot>,
+    pub last_error: Option<PerformError>,
+}
+
+impl StoreSnapshot {
+    pub fn pending(&self) -> usize {
+        self.entries.iter().filter(|entry| !entry.ready).count()
+    }
+    pub fn ready(&self) -> usize {
+        self.entries.iter().filter(|entry| entry.ready).count()
+    }
+}
+
+pub fn snapshots() -> Vec<StoreSnapshot> {
+    let registry = REGISTRY
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let snapshots: Vec<_> = registry.iter().map(|(_, snapshot)| *snapshot).collect();
+    drop(registry);
+    snapshots.into_iter().map(|snapshot| snapshot()).collect()
+}
+
+pub(crate) fn register<P: StoreProvider>() {
+    let name = std::any::type_name::<P>();
+    let mut registry = REGISTRY
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if registry.iter().all(|(registered, _)| *registered != name) {
+        registry.push((name, snapshot::<P>));
+    }
+}
+
+#[cfg(feature = "devtools")]
+impl EntrySnapshot {
+    fn state(&self) -> &'static str {
+        match (self.ready, &self.error) {
+            (_, Some(_)) => "failed",
+            (true, None) => "ready",
+            (false, None) => "pending",
+        }
+    }
+}
+
+#[cfg(feature = "devtools")]
+pub fn dump() -> serde_json::Value {
+    use serde_json::json;
+    let error = |error: &Option<PerformError>| error.as_ref().map(ToString::to_string);
+    let stores = snapshots().into_iter().map(|snapshot| {
+        let entries = snapshot.entries.iter().map(|entry| {
+            json!({
+                "id": entry.id.to_string(),
+                "state": entry.state(),
+                "age_ms": entry.age.map(|age| age.as_millis() as u64),
+                "error": error(&entry.error),
+            })
+        });
+        json!({
+            "name": snapshot.name,
+            "locked": snapshot.locked,
+            "in_flight": snapshot.in_flight,
+            "queued": snapshot.queued,
+            "pending": snapshot.pending(),
+            "ready": snapshot.ready(),
+            "last_error": error(&snapshot.last_error),
+            "entries": entries.collect::<Vec<_>>(),
+        })
+    });
+    serde_json::Value::Array(stores.collect())
+}
+
+#[cfg(all(target_arch = "wasm32", feature = "devtools"))]
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub fn perform_wasm_dump() -> wasm_bindgen::JsValue {
+    let dumped = dump();
+    let pretty = serde_json::to_string_pretty(&dumped).unwrap_or_default();
+    web_sys::console::log_1(&pretty.into());
+    js_sys::JSON::parse(&dumped.to_string()).unwrap_or(wasm_bindgen::JsValue::NULL)
+}
+
+fn snapshot<P: StoreProvider>() -> StoreSnapshot {
+    let shared = P::shared();
+    let (locked, entries, last_error) = match shared.store().try_lock() {
+        Ok(store) => {
+            let (entries, last_error) = store.inspect();
+            (false, entries, last_error)
+        }
+        Err(_) => (true, Vec::new(), None),
+    };
+    StoreSnapshot {
+        name: std::any::type_name::<P>(),
+        locked,
+        in_flight: shared.in_flight(),
+        queued: shared.queued(),
+        entries,
+        last_error,
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::snapshots;
+    use crate::Perform;
+
+    mod inspected {
+        crate::build_perform!(u8);
+    }
+
+    #[tokio::test]
+    async fn activated_stores_show_up_with_their_entries() {
+        let idle = inspected::Session::activate().await;
+        let done = inspected::Session::activate().await;
+        done.perform(async { 1 }).await;
+        let snapshot = snapshots()
+            .into_iter()
+            .find(|snapshot| snapshot.name.ends_with("inspected::Provider"))
+            .unwrap();
+        assert!(!snapshot.locked);
+        assert_eq!((snapshot.pending(), snapshot.ready()), (1, 1));
+        let entry = snapshot.entries.iter().find(|entry| entry.id == *done.id());
+        assert!(entry.is_some_and(|entry| entry.ready && entry.age.is_some()));
+        drop(idle);
+    }
+
+    #[tokio::test]
+    #[cfg(all(not(target_arch = "wasm32"), feature = "devtools"))]
+    async fn dump_lists_entry_states_as_json() {
+        let failed = inspected::Session::activate().await;
+        failed
+            .perform_result(async { Err(crate::PerformError::Busy) })
+            .await;
+        let dumped = super::dump();
+        let store = dumped
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|store| {
+                store["name"]
+                    .as_str()
+                    .unwrap()
+                    .ends_with("inspected::Provider")
+            })
+            .unwrap();
+        let entry = store["entries"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|entry| entry["id"] == failed.id().to_string())
+            .unwrap();
+        assert_eq!(entry["state"], "failed");
+        assert_eq!(entry["error"], "Busy");
+        assert!(entry["age_ms"].is_u64());
+    }
+}
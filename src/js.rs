@@ -0,0 +1,138 @@
+use crate::{PerformError, PerformHandle, Session, StoreProvider};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::JsFuture;
+
+pub async fn resolve(promise: js_sys::Promise) -> Result<JsValue, PerformError> {
+    JsFuture::from(promise).await.map_err(PerformError::from)
+}
+
+impl<P: StoreProvider> Session<P> {
+    pub fn perform_js<F>(&self, promise: js_sys::Promise, convert: F) -> PerformHandle
+    where
+        F: FnOnce(JsValue) -> Result<P::Value, PerformError> + 'static,
+    {
+        self.perform_result_with_spawn_local(async move { convert(resolve(promise).await?) })
+    }
+
+    #[cfg(feature = "js-serde")]
+    pub fn perform_js_serde(&self, promise: js_sys::Promise) -> PerformHandle
+    where
+        P::Value: serde::de::DeserializeOwned,
+    {
+        self.perform_js(promise, |value| {
+            serde_wasm_bindgen::from_value(value).map_err(|error| JsValue::from(error).into())
+        })
+    }
+}
+
+#[cfg(feature = "js-serde")]
+mod export {
+    use crate::{Perform, PerformError, Session, StoreProvider, TaskMessage, Uuid};
+    use futures::future::{FutureExt, LocalBoxFuture};
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::rc::{Rc, Weak};
+    use std::time::Duration;
+    use wasm_bindgen::prelude::wasm_bindgen;
+    use wasm_bindgen::JsValue;
+
+    const DEFAULT_TAKE_TIMEOUT: Duration = Duration::from_secs(30);
+
+    trait Exported {
+        fn is_alive(&self) -> bool;
+        fn take(
+            &self,
+            timeout: Duration,
+            enveloped: bool,
+        ) -> LocalBoxFuture<'static, Result<JsValue, PerformError>>;
+    }
+
+    impl<P: StoreProvider> Exported for Weak<Session<P>>
+    where
+        P::Value: serde::Serialize,
+    {
+        fn is_alive(&self) -> bool {
+            self.strong_count() > 0
+        }
+
+        fn take(
+            &self,
+            timeout: Duration,
+            enveloped: bool,
+        ) -> LocalBoxFuture<'static, Result<JsValue, PerformError>> {
+            let session = self.upgrade();
+            async move {
+                let session = session.ok_or(PerformError::Closed)?;
+                let id = *session.id();
+                let taken = session
+                    .take_when(timeout, |store| {
+                        let task_id = store.version(&id).unwrap_or_default();
+                        match store.remove(&id) {
+                            None | Some(Err(PerformError::Empty)) => Err(PerformError::Empty),
+                            Some(result) => Ok((task_id, result)),
+                        }
+                    })
+                    .await;
+                let (task_id, result) = taken.unwrap_or_else(|error| (0, Err(error)));
+                if enveloped {
+                    let store_id = std::any::type_name::<P>();
+                    return TaskMessage::new(task_id, store_id, result).to_js();
+                }
+                serde_wasm_bindgen::to_value(&result?).map_err(|error| JsValue::from(error).into())
+            }
+            .boxed_local()
+        }
+    }
+
+    thread_local! {
+        static EXPORTED: RefCell<HashMap<Uuid, Box<dyn Exported>>> = RefCell::default();
+    }
+
+    impl<P: StoreProvider> Session<P>
+    where
+        P::Value: serde::Serialize,
+    {
+        pub fn export(self: &Rc<Self>) -> String {
+            let id = *self.id();
+            EXPORTED.with(|exported| {
+                let mut exported = exported.borrow_mut();
+                exported.retain(|_, session| session.is_alive());
+                exported.insert(id, Box::new(Rc::downgrade(self)));
+            });
+            id.to_string()
+        }
+    }
+
+    fn take(id: &str, timeout_ms: Option<f64>, enveloped: bool) -> js_sys::Promise {
+        let timeout = timeout_ms.map_or(DEFAULT_TAKE_TIMEOUT, |ms| {
+            Duration::try_from_secs_f64(ms.max(0.0) / 1000.0).unwrap_or(Duration::MAX)
+        });
+        let taken = Uuid::parse_str(id).ok().and_then(|id| {
+            EXPORTED.with(|exported| Some(exported.borrow().get(&id)?.take(timeout, enveloped)))
+        });
+        wasm_bindgen_futures::future_to_promise(async move {
+            match taken {
+                Some(taken) => taken.await.map_err(JsValue::from),
+                None => Err(PerformError::Closed.into()),
+            }
+        })
+    }
+
+    #[wasm_bindgen(js_name = takeSession)]
+    pub fn take_session(id: &str, timeout_ms: Option<f64>) -> js_sys::Promise {
+        take(id, timeout_ms, false)
+    }
+
+    #[wasm_bindgen(js_name = takeSessionMessage)]
+    pub fn take_session_message(id: &str, timeout_ms: Option<f64>) -> js_sys::Promise {
+        take(id, timeout_ms, true)
+    }
+
+    #[wasm_bindgen(js_name = releaseSession)]
+    pub fn release_session(id: &str) -> bool {
+        Uuid::parse_str(id)
+            .is_ok_and(|id| EXPORTED.with(|exported| exported.borrow_mut().remove(&id).is_some()))
+    }
+}
+#[cfg(feature = "js-serde")]
+pub use export::{release_session, take_session, take_session_message};
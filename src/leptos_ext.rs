@@ -0,0 +1,63 @@
+use crate::{PerformError, PollAdapter, Session, StoreProvider};
+use leptos::prelude::{ArcReadSignal, ArcRwSignal, ArcTrigger, LocalResource, Notify, Set};
+use std::future::Future;
+
+impl PollAdapter for ArcTrigger {
+    fn on_pending(&self) {
+        self.notify();
+    }
+    fn on_complete(&self) {
+        self.notify();
+    }
+}
+
+type Completion<T> = Option<Result<T, PerformError>>;
+
+impl<P: StoreProvider> Session<P>
+where
+    P::Value: Clone + Sync,
+{
+    pub fn try_signal(&self) -> Result<ArcReadSignal<Completion<P::Value>>, PerformError> {
+        let signal = ArcRwSignal::new(None);
+        let writer = signal.write_only();
+        self.try_on_complete(move |result| writer.set(Some(result.clone())))?;
+        Ok(signal.read_only())
+    }
+    pub async fn signal(&self) -> ArcReadSignal<Completion<P::Value>> {
+        let signal = ArcRwSignal::new(None);
+        let writer = signal.write_only();
+        self.on_complete(move |result| writer.set(Some(result.clone())))
+            .await;
+        signal.read_only()
+    }
+}
+
+pub fn local_resource<P, F, Fut>(factory: F) -> LocalResource<Result<P::Value, PerformError>>
+where
+    P: StoreProvider,
+    F: Fn() -> Fut + 'static,
+    Fut: Future<Output = P::Value> + 'static,
+{
+    LocalResource::new(move || Session::<P>::perform_and_take(factory()))
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use crate::Perform;
+    use leptos::prelude::GetUntracked;
+
+    mod signalled {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    async fn signal_follows_every_completion() {
+        let session = signalled::Session::activate().await;
+        let signal = session.signal().await;
+        assert!(signal.get_untracked().is_none());
+        session.perform(async { 1 }).await;
+        assert!(matches!(signal.get_untracked(), Some(Ok(1))));
+        session.perform(async { 2 }).await;
+        assert!(matches!(signal.get_untracked(), Some(Ok(2))));
+    }
+}
@@ -1,4 +1,5 @@
 pub use async_trait::async_trait;
+pub use futures::future::AbortHandle;
 pub use once_cell::sync::OnceCell;
 pub use thiserror::Error;
 pub use tokio::sync::Mutex;
@@ -9,14 +10,134 @@ pub use wasm_bindgen_futures::spawn_local;
 
 use std::collections::HashMap;
 
-#[async_trait]
+mod adapter;
+#[cfg(feature = "anyhow")]
+pub mod anyhow_ext;
+mod batcher;
+mod budget;
+#[cfg(feature = "worker")]
+mod bytes;
+#[cfg(all(target_arch = "wasm32", feature = "cache-storage"))]
+pub mod cache_storage;
+mod cancel;
+mod combinators;
+mod config;
+pub mod connectivity;
+#[cfg(all(target_arch = "wasm32", feature = "broadcast"))]
+pub mod cross_tab;
+mod dedup;
+mod display;
+#[cfg(feature = "egui")]
+pub mod egui_ext;
+#[cfg(feature = "egui")]
+pub mod egui_inspector;
+#[cfg(feature = "serde")]
+mod envelope;
+mod error;
+#[cfg(all(target_arch = "wasm32", feature = "fetch"))]
+pub mod fetch;
+#[cfg(all(target_arch = "wasm32", feature = "web-sys"))]
+pub mod frame;
+mod group;
+mod handle;
+#[cfg(any(
+    all(target_arch = "wasm32", feature = "gloo-net"),
+    all(not(target_arch = "wasm32"), feature = "reqwest")
+))]
+pub mod http;
+#[cfg(all(target_arch = "wasm32", feature = "idb"))]
+pub mod idb;
+pub mod inspect;
+#[cfg(all(target_arch = "wasm32", feature = "web-sys"))]
+pub mod js;
+#[cfg(feature = "leptos")]
+pub mod leptos_ext;
+pub mod lifecycle;
+mod mapped;
+mod meta;
+mod paged;
+#[cfg(feature = "perf-marks")]
+mod perf;
+mod performer;
+mod performer_map;
+#[cfg(all(target_arch = "wasm32", feature = "persist"))]
+pub mod persist;
+#[cfg(feature = "persistence")]
+mod persistence;
+mod prefetch;
+mod progress;
+#[cfg(feature = "poll-promise")]
+mod promise;
+mod reaper;
+mod repeat;
+#[cfg(feature = "resumable")]
+pub mod resumable;
+mod retry;
+mod session;
+mod spawn;
+mod store;
+mod streaming;
+mod submit;
+#[cfg(feature = "wasm-threads")]
+pub mod threads;
+pub mod time;
+mod watch;
+#[cfg(feature = "worker")]
+pub mod worker;
+#[cfg(feature = "yew")]
+pub mod yew_ext;
+
+pub use adapter::{PollAdapter, PollEvent};
+pub use batcher::Batcher;
+pub use budget::FrameBudget;
+#[cfg(feature = "worker")]
+pub use bytes::Bytes;
+pub use cancel::CancellationToken;
+pub use config::{
+    CircuitBreaker, CompletionOrder, EvictionPolicy, PerformConfig, Priority, RateLimit,
+    RateLimitOverflow, ThrottleMode,
+};
+pub use display::{BadgeState, PerformerExt};
+#[cfg(feature = "serde")]
+pub use envelope::{PerformEnvelope, TaskMessage, TASK_MESSAGE_VERSION};
+pub use error::{PerformError, RemoteError};
+pub use group::{GroupError, SessionGroup};
+pub use handle::PerformHandle;
+pub use mapped::Mapped;
+pub use meta::{CacheStatus, TaskMeta};
+pub use paged::{Page, PagedLoader};
+pub use performer::{Performer, PerformerState};
+pub use performer_map::PerformerMap;
+pub use prefetch::Prefetcher;
+pub use progress::ProgressSink;
+pub use reaper::Reaper;
+pub use repeat::Repeating;
+pub use retry::{Backoff, RetryPolicy};
+pub use session::Session;
+#[cfg(not(target_arch = "wasm32"))]
+pub use spawn::set_spawner;
+pub use spawn::{pump, spawn_portable, MaybeSend};
+pub use store::{
+    Completed, Completion, DrainReport, HealReport, InsertOptions, SharedStore, Store, StoreEvent,
+    StoreProvider,
+};
+pub use streaming::PartialSender;
+pub use submit::SubmitGuard;
+pub use time::{Instant, SystemTime};
+pub use watch::Watch;
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 pub trait Perform<T> {
+    fn id(&self) -> &Uuid;
+
     #[allow(dead_code)]
     fn try_activate() -> Self;
     async fn activate() -> Self;
 
     #[allow(dead_code)]
-    fn perform_with_spawn_local<Fut>(&self, fut: Fut)
+    #[deprecated(note = "non-Send futures go through LocalPerform::perform_with_spawn_local")]
+    fn perform_with_spawn_local<Fut>(&self, fut: Fut) -> PerformHandle
     where
         Fut: std::future::Future<Output = T> + 'static;
     async fn perform<Fut>(&self, fut: Fut)
@@ -40,12 +161,36 @@ pub trait Perform<T> {
     ) -> Option<Result<T, PerformError>>;
 }
 
-#[derive(Debug, Error, Clone)]
-pub enum PerformError {
-    #[error("Locked")]
-    Locked,
-    #[error("Empty")]
-    Empty,
+#[async_trait(?Send)]
+pub trait LocalPerform<T> {
+    fn id(&self) -> &Uuid;
+
+    #[allow(dead_code)]
+    fn try_activate() -> Self;
+    async fn activate() -> Self;
+
+    fn perform_with_spawn_local<Fut>(&self, fut: Fut) -> PerformHandle
+    where
+        Fut: std::future::Future<Output = T> + 'static;
+    async fn perform<Fut>(&self, fut: Fut)
+    where
+        Fut: std::future::Future<Output = T> + 'static;
+
+    fn try_ready(&self) -> Result<T, PerformError>;
+
+    fn try_take(&self) -> Result<T, PerformError>;
+    async fn take(&self) -> Result<T, PerformError>;
+
+    fn take_from_id(
+        &self,
+        hash_map: &mut HashMap<Uuid, Result<T, PerformError>>,
+        id: &Uuid,
+    ) -> Result<T, PerformError>;
+    fn get_as_take(
+        &self,
+        hash_map: &mut HashMap<Uuid, Result<T, PerformError>>,
+        id: &Uuid,
+    ) -> Option<Result<T, PerformError>>;
 }
 
 #[allow(dead_code)]
@@ -59,158 +204,20 @@ pub fn ok_or_empty<T>(option: Option<Result<T, PerformError>>) -> Result<T, Perf
 #[macro_export]
 macro_rules! build_perform {
     ($value:ty) => {
-        use std::collections::HashMap;
-        use std::future::Future;
-        type V = $value;
-        type E = $crate::PerformError;
-        type H = HashMap<$crate::Uuid, Result<V, E>>;
-
-        static STORE: $crate::OnceCell<$crate::Mutex<H>> = $crate::OnceCell::new();
-
-        fn global_data() -> &'static $crate::Mutex<H> {
-            STORE.get_or_init(|| {
-                let hash_map = HashMap::new();
-                $crate::Mutex::new(hash_map)
-            })
-        }
-
-        fn try_lock_and_do_mut<F>(f: F) -> Result<V, E>
-        where
-            F: FnOnce(&mut H) -> Result<V, E>,
-        {
-            let try_lock = global_data().try_lock();
-            match try_lock {
-                Ok(mut hash_map) => f(&mut *hash_map),
-                Err(_) => Err(E::Locked),
-            }
-        }
-        async fn lock_and_do_mut<F, R>(f: F) -> R
-        where
-            F: FnOnce(&mut H) -> R,
-        {
-            let mut hash_map = global_data().lock().await;
-            f(&mut *hash_map)
-        }
-
-        pub struct Session {
-            #[allow(dead_code)]
-            id: $crate::Uuid,
-        }
-
-        #[$crate::async_trait]
-        impl $crate::Perform<V> for Session {
-            #[allow(dead_code)]
-            fn try_activate() -> Self {
-                let id = $crate::Uuid::new_v4();
-                let _ = try_lock_and_do_mut(|hash_map| {
-                    let option = hash_map.insert(id, Err(E::Empty));
-                    $crate::ok_or_empty(option)
-                });
-                Self { id }
-            }
-            async fn activate() -> Self {
-                let id = $crate::Uuid::new_v4();
-                lock_and_do_mut(|hash_map| hash_map.insert(id, Err(E::Empty))).await;
-                Self { id }
-            }
-
-            #[allow(dead_code)]
-            fn perform_with_spawn_local<Fut>(&self, fut: Fut)
-            where
-                Fut: Future<Output = V> + 'static,
-            {
-                let id = self.id.clone();
-                $crate::spawn_local(async move {
-                    let value = fut.await;
-                    lock_and_do_mut(|hash_map| hash_map.insert(id, Ok(value))).await;
-                });
-            }
-            async fn perform<Fut>(&self, fut: Fut)
-            where
-                Fut: Future<Output = V> + 'static + Send,
-            {
-                let id = self.id.clone();
-                let value = fut.await;
-                lock_and_do_mut(|hash_map| hash_map.insert(id, Ok(value))).await;
-            }
-
-            fn try_ready(&self) -> Result<V, E> {
-                let id = self.id.clone();
-                try_lock_and_do_mut(|hash_map| {
-                    let option = hash_map.insert(id, Err(E::Empty));
-                    $crate::ok_or_empty(option)
-                })
-            }
+        static STORE: $crate::OnceCell<$crate::SharedStore<$value>> = $crate::OnceCell::new();
 
-            fn try_take(&self) -> Result<V, E> {
-                try_lock_and_do_mut(|hash_map| self.take_from_id(hash_map, &self.id))
-            }
-            async fn take(&self) -> Result<V, E> {
-                lock_and_do_mut(|hash_map| self.take_from_id(hash_map, &self.id)).await
-            }
-
-            fn take_from_id(&self, hash_map: &mut H, id: &$crate::Uuid) -> Result<V, E> {
-                let option = self.get_as_take(hash_map, id);
-                $crate::ok_or_empty(option)
-            }
-            fn get_as_take(&self, hash_map: &mut H, id: &$crate::Uuid) -> Option<Result<V, E>> {
-                hash_map.remove_entry(id).map(|(_id, r)| r)
+        pub struct Provider;
+        impl $crate::StoreProvider for Provider {
+            type Value = $value;
+            fn shared() -> &'static $crate::SharedStore<$value> {
+                STORE.get_or_init($crate::SharedStore::default)
             }
         }
 
-        #[derive(PartialEq)]
-        enum Progress {
-            Triggered,
-            Off,
-        }
-
         #[allow(dead_code)]
-        pub struct Performer {
-            session: Session,
-            progress: Progress,
-        }
-        impl Performer {
-            #[allow(dead_code)]
-            pub fn new(session: Session) -> Self {
-                let instance = Self {
-                    session: session,
-                    progress: Progress::Off,
-                };
-                return instance;
-            }
-            #[allow(dead_code)]
-            pub fn try_take(&mut self) -> Result<V, E> {
-                use $crate::Perform as _;
-
-                self.session.try_take().and_then(|v| {
-                    self.progress = Progress::Off;
-                    Ok(v)
-                })
-            }
-            #[allow(dead_code)]
-            pub async fn perform_one_time_or_not<F>(&mut self, fut: F)
-            where
-                F: std::future::Future<Output = V> + 'static + Send,
-            {
-                use $crate::Perform as _;
-                if self.progress == Progress::Off {
-                    self.session.perform(fut).await;
-                    self.progress = Progress::Triggered;
-                }
-            }
-            #[allow(dead_code)]
-            pub fn perform_one_time_or_not_with_spawn_local<F>(&mut self, fut: F)
-            where
-                F: std::future::Future<Output = V> + 'static,
-            {
-                use $crate::Perform as _;
-                if self.progress == Progress::Off {
-                    let _is_ready = self.session.try_ready();
-                    self.session.perform_with_spawn_local(fut);
-                    self.progress = Progress::Triggered;
-                }
-            }
-        }
+        pub type Session = $crate::Session<Provider>;
+        #[allow(dead_code)]
+        pub type Performer = $crate::Performer<Provider>;
     };
 }
 
@@ -219,7 +226,26 @@ mod tests {
     #[allow(unused_imports)]
     use crate::{Perform, PerformError};
 
-    #[allow(dead_code)]
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+    #[cfg(target_arch = "wasm32")]
+    pub(crate) async fn polled<T, F>(mut try_take: F) -> Result<T, PerformError>
+    where
+        F: FnMut() -> Result<T, PerformError>,
+    {
+        for _ in 0..200 {
+            match try_take() {
+                Err(PerformError::Empty | PerformError::Locked) => {
+                    crate::time::sleep(std::time::Duration::from_millis(5)).await
+                }
+                taken => return taken,
+            }
+        }
+        Err(PerformError::TimedOut)
+    }
+
+    #[allow(dead_code, clippy::assertions_on_constants)]
     async fn run_test<Fut, T, A, S>(fut: Fut, assert: A, session: S) -> anyhow::Result<()>
     where
         Fut: std::future::Future<Output = T> + 'static + Send,
@@ -245,6 +271,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn first_test() {
         assert!(true);
     }
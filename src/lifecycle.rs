@@ -0,0 +1,113 @@
+use crate::{PerformError, StoreProvider, Uuid};
+use futures::stream::{self, Stream};
+use std::sync::OnceLock;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+const BUS_CAPACITY: usize = 256;
+
+static BUS: OnceLock<broadcast::Sender<LifecycleEvent>> = OnceLock::new();
+
+#[derive(Debug, Clone)]
+pub enum Lifecycle {
+    Started,
+    Progress(f32),
+    Completed,
+    Failed(PerformError),
+    Cancelled,
+}
+
+impl Lifecycle {
+    pub(crate) fn settled<T>(result: &Result<T, PerformError>) -> Self {
+        match result {
+            Ok(_) => Self::Completed,
+            Err(PerformError::Cancelled) => Self::Cancelled,
+            Err(error) => Self::Failed(error.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LifecycleEvent {
+    pub store: &'static str,
+    pub id: Uuid,
+    pub generation: u64,
+    pub lifecycle: Lifecycle,
+}
+
+fn bus() -> &'static broadcast::Sender<LifecycleEvent> {
+    BUS.get_or_init(|| broadcast::Sender::new(BUS_CAPACITY))
+}
+
+pub fn subscribe() -> impl Stream<Item = LifecycleEvent> {
+    stream::unfold(bus().subscribe(), |mut events| async move {
+        loop {
+            match events.recv().await {
+                Ok(event) => return Some((event, events)),
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    })
+}
+
+pub fn is_observed() -> bool {
+    BUS.get().is_some_and(|bus| bus.receiver_count() > 0)
+}
+
+pub(crate) fn emit<P: StoreProvider>(id: Uuid, generation: u64, lifecycle: Lifecycle) {
+    emit_from(std::any::type_name::<P>(), id, generation, lifecycle)
+}
+pub(crate) fn is_recorded() -> bool {
+    cfg!(feature = "perf-marks") || is_observed()
+}
+
+pub(crate) fn emit_from(store: &'static str, id: Uuid, generation: u64, lifecycle: Lifecycle) {
+    #[cfg(feature = "perf-marks")]
+    crate::perf::record(store, id, generation, &lifecycle);
+    if is_observed() {
+        let _ = bus().send(LifecycleEvent {
+            store,
+            id,
+            generation,
+            lifecycle,
+        });
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::Lifecycle;
+    use crate::{Perform, PerformError};
+    use futures::StreamExt;
+
+    mod observed {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    async fn every_task_reports_start_and_outcome() {
+        let session = observed::Session::activate().await;
+        let id = *session.id();
+        let collected = tokio::spawn(
+            super::subscribe()
+                .filter(move |event| std::future::ready(event.id == id))
+                .take(4)
+                .collect::<Vec<_>>(),
+        );
+        session.perform(async { 1 }).await;
+        session
+            .perform_result(async { Err(PerformError::Busy) })
+            .await;
+
+        let events = collected.await.unwrap();
+        assert!(events[0].store.ends_with("observed::Provider"));
+        assert!(matches!(events[0].lifecycle, Lifecycle::Started));
+        assert!(matches!(events[1].lifecycle, Lifecycle::Completed));
+        assert!(matches!(events[2].lifecycle, Lifecycle::Started));
+        assert_eq!(events[3].generation, 2);
+        assert!(matches!(
+            events[3].lifecycle,
+            Lifecycle::Failed(PerformError::Busy)
+        ));
+    }
+}
@@ -0,0 +1,94 @@
+use crate::{Perform, PerformError, Session, StoreProvider};
+
+pub struct Mapped<'a, P, F> {
+    session: &'a Session<P>,
+    f: F,
+}
+impl<'a, P, F, U> Mapped<'a, P, F>
+where
+    P: StoreProvider,
+    F: Fn(P::Value) -> Result<U, PerformError>,
+{
+    pub fn session(&self) -> &'a Session<P> {
+        self.session
+    }
+    pub fn try_take(&self) -> Result<U, PerformError> {
+        self.session.try_take().and_then(&self.f)
+    }
+    pub async fn take(&self) -> Result<U, PerformError> {
+        self.session.take().await.and_then(&self.f)
+    }
+    pub fn map<V, G>(self, g: G) -> Mapped<'a, P, impl Fn(P::Value) -> Result<V, PerformError>>
+    where
+        G: Fn(U) -> V,
+    {
+        let f = self.f;
+        Mapped {
+            session: self.session,
+            f: move |value| f(value).map(&g),
+        }
+    }
+    pub fn and_then<V, E, G>(
+        self,
+        g: G,
+    ) -> Mapped<'a, P, impl Fn(P::Value) -> Result<V, PerformError>>
+    where
+        G: Fn(U) -> Result<V, E>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let f = self.f;
+        Mapped {
+            session: self.session,
+            f: move |value| f(value).and_then(|value| g(value).map_err(PerformError::task)),
+        }
+    }
+}
+
+impl<P: StoreProvider> Session<P> {
+    pub fn map<U, F>(&self, f: F) -> Mapped<'_, P, impl Fn(P::Value) -> Result<U, PerformError>>
+    where
+        F: Fn(P::Value) -> U,
+    {
+        Mapped {
+            session: self,
+            f: move |value| Ok(f(value)),
+        }
+    }
+    pub fn and_then<U, E, F>(
+        &self,
+        f: F,
+    ) -> Mapped<'_, P, impl Fn(P::Value) -> Result<U, PerformError>>
+    where
+        F: Fn(P::Value) -> Result<U, E>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        Mapped {
+            session: self,
+            f: move |value| f(value).map_err(PerformError::task),
+        }
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use crate::{Perform, PerformError};
+
+    mod raw {
+        crate::build_perform!(String);
+    }
+
+    #[tokio::test]
+    async fn and_then_parses_at_take_time_and_folds_errors() {
+        let session = raw::Session::activate().await;
+        let parsed = session
+            .and_then(|text| serde_json::from_str::<u32>(&text))
+            .map(|number| number * 2);
+
+        session.perform(async { "21".to_string() }).await;
+        assert_eq!(parsed.take().await.unwrap(), 42);
+
+        session.perform(async { "oops".to_string() }).await;
+        assert!(matches!(parsed.take().await, Err(PerformError::Task(_))));
+        assert!(matches!(parsed.try_take(), Err(PerformError::Empty)));
+    }
+}
@@ -0,0 +1,25 @@
+use crate::time::Instant;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheStatus {
+    Hit,
+    Miss,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaskMeta {
+    pub started_at: Instant,
+    pub completed_at: Instant,
+    pub attempts: u32,
+    pub cache: Option<CacheStatus>,
+}
+
+impl TaskMeta {
+    pub fn duration(&self) -> Duration {
+        self.completed_at.saturating_duration_since(self.started_at)
+    }
+    pub fn age(&self) -> Duration {
+        self.completed_at.elapsed()
+    }
+}
@@ -0,0 +1,138 @@
+use crate::{MaybeSend, PerformError, PerformerMap, StoreProvider};
+use std::future::Future;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub has_more: bool,
+}
+
+impl<T> Page<T> {
+    pub fn new(items: Vec<T>, has_more: bool) -> Self {
+        Self { items, has_more }
+    }
+}
+
+pub struct PagedLoader<T, P: StoreProvider<Value = Page<T>>> {
+    pages: PerformerMap<usize, P>,
+    items: Vec<T>,
+    next_page: usize,
+    has_more: bool,
+    error: Option<PerformError>,
+}
+
+impl<T, P: StoreProvider<Value = Page<T>>> PagedLoader<T, P> {
+    pub fn new() -> Self {
+        Self {
+            pages: PerformerMap::new(),
+            items: Vec::new(),
+            next_page: 0,
+            has_more: true,
+            error: None,
+        }
+    }
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+    pub fn has_more(&self) -> bool {
+        self.has_more
+    }
+    pub fn next_page(&self) -> usize {
+        self.next_page
+    }
+    pub fn is_loading(&self) -> bool {
+        self.pages.contains_key(&self.next_page)
+    }
+    pub fn error(&self) -> Option<&PerformError> {
+        self.error.as_ref()
+    }
+    pub fn near_end(&self, last_visible: usize, margin: usize) -> bool {
+        last_visible + margin + 1 >= self.items.len()
+    }
+    pub fn load_next_page<F, Fut>(&mut self, factory: F) -> bool
+    where
+        F: FnOnce(usize) -> Fut,
+        Fut: Future<Output = Page<T>> + 'static + MaybeSend,
+    {
+        if !self.can_load() {
+            return false;
+        }
+        let page = self.next_page;
+        self.error = None;
+        self.pages.get_or_spawn(page, || factory(page));
+        true
+    }
+    pub fn poll(&mut self) -> usize {
+        let page = self.next_page;
+        match self.pages.try_take(&page) {
+            Ok(loaded) => {
+                self.pages.remove(&page);
+                let added = loaded.items.len();
+                self.items.extend(loaded.items);
+                self.has_more = loaded.has_more;
+                self.next_page += 1;
+                added
+            }
+            Err(PerformError::Empty | PerformError::Locked) => 0,
+            Err(error) => {
+                self.pages.remove(&page);
+                self.error = Some(error);
+                0
+            }
+        }
+    }
+    pub fn reset(&mut self) {
+        self.pages.retain_present(None);
+        self.items.clear();
+        self.next_page = 0;
+        self.has_more = true;
+        self.error = None;
+    }
+    fn can_load(&self) -> bool {
+        self.has_more && !self.is_loading()
+    }
+}
+
+impl<T, P: StoreProvider<Value = Page<T>>> Default for PagedLoader<T, P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::{Page, PagedLoader};
+    use std::time::Duration;
+
+    mod numbers {
+        crate::build_perform!(super::Page<u32>);
+    }
+
+    #[tokio::test]
+    async fn pages_accumulate_until_the_last_one() {
+        let mut loader = PagedLoader::<u32, numbers::Provider>::new();
+        let fetch = |page: usize| async move {
+            let start = page as u32 * 2;
+            Page::new(vec![start, start + 1], page < 1)
+        };
+        assert!(loader.load_next_page(fetch));
+        assert!(!loader.load_next_page(fetch));
+        while loader.has_more() {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            if loader.poll() > 0 {
+                loader.load_next_page(fetch);
+            }
+        }
+        assert_eq!(loader.items(), &[0, 1, 2, 3]);
+        assert_eq!(loader.next_page(), 2);
+        assert!(!loader.load_next_page(fetch));
+        assert!(loader.near_end(3, 0));
+        assert!(!loader.near_end(1, 1));
+    }
+}
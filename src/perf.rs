@@ -0,0 +1,183 @@
+use crate::lifecycle::Lifecycle;
+use crate::Uuid;
+
+fn label(store: &str, id: Uuid, generation: u64) -> String {
+    let store = store.rsplit("::").nth(1).unwrap_or(store);
+    format!("perform {store} {id}#{generation}")
+}
+
+#[cfg(target_arch = "wasm32")]
+mod marks {
+    use wasm_bindgen::{JsCast, JsValue};
+    use web_sys::Performance;
+
+    fn performance() -> Option<Performance> {
+        js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str("performance"))
+            .ok()?
+            .dyn_into::<Performance>()
+            .ok()
+    }
+
+    pub(super) fn start(label: &str) {
+        if let Some(performance) = performance() {
+            let _ = performance.mark(&format!("{label} start"));
+        }
+    }
+
+    pub(super) fn end(label: &str) {
+        let Some(performance) = performance() else {
+            return;
+        };
+        let (start, end) = (format!("{label} start"), format!("{label} end"));
+        if performance.mark(&end).is_ok() {
+            let _ = performance.measure_with_start_mark_and_end_mark(label, &start, &end);
+        }
+        performance.clear_marks_with_mark_name(&start);
+        performance.clear_marks_with_mark_name(&end);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod marks {
+    use std::collections::HashMap;
+    use std::sync::{Mutex, OnceLock};
+
+    static SPANS: OnceLock<Mutex<HashMap<String, tracing::Span>>> = OnceLock::new();
+
+    fn spans() -> std::sync::MutexGuard<'static, HashMap<String, tracing::Span>> {
+        SPANS
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub(super) fn start(label: &str) {
+        let span = tracing::info_span!("perform", task = label);
+        spans().insert(label.to_string(), span);
+    }
+
+    pub(super) fn end(label: &str) {
+        spans().remove(label);
+    }
+
+    pub(super) fn span(label: &str) -> tracing::Span {
+        spans()
+            .get(label)
+            .cloned()
+            .unwrap_or_else(tracing::Span::none)
+    }
+
+    #[cfg(test)]
+    pub(super) fn is_open(label: &str) -> bool {
+        spans().contains_key(label)
+    }
+}
+
+pub(crate) fn record(store: &str, id: Uuid, generation: u64, lifecycle: &Lifecycle) {
+    match lifecycle {
+        Lifecycle::Started => marks::start(&label(store, id, generation)),
+        Lifecycle::Progress(_) => {}
+        Lifecycle::Completed | Lifecycle::Failed(_) | Lifecycle::Cancelled => {
+            marks::end(&label(store, id, generation))
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn in_span<P, F>(
+    id: Uuid,
+    generation: u64,
+    fut: F,
+) -> tracing::instrument::Instrumented<F>
+where
+    P: crate::StoreProvider,
+{
+    use tracing::Instrument;
+    let label = label(std::any::type_name::<P>(), id, generation);
+    fut.instrument(marks::span(&label))
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(not(target_arch = "wasm32"))]
+    use crate::Perform;
+    #[cfg(not(target_arch = "wasm32"))]
+    use std::time::Duration;
+
+    mod profiled {
+        crate::build_perform!(u32);
+    }
+
+    #[test]
+    fn labels_name_the_store_module() {
+        let id = crate::Uuid::nil();
+        let label = super::label("app::profiled::Provider", id, 3);
+        assert_eq!(label, format!("perform profiled {id}#3"));
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn spans_close_when_the_task_settles() {
+        let session = profiled::Session::activate().await;
+        let (sender, receiver) = tokio::sync::oneshot::channel::<()>();
+        session.perform_spawned(async move {
+            let _ = receiver.await;
+            1
+        });
+        let label = super::label(
+            std::any::type_name::<profiled::Provider>(),
+            *session.id(),
+            1,
+        );
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(super::marks::is_open(&label));
+        sender.send(()).unwrap();
+        let taken = session.try_take_timeout(Duration::from_secs(1)).await;
+        assert_eq!(taken.unwrap(), 1);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!super::marks::is_open(&label));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    struct Entered(std::sync::Arc<std::sync::atomic::AtomicUsize>);
+
+    #[cfg(not(target_arch = "wasm32"))]
+    impl tracing::Subscriber for Entered {
+        fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+        fn new_span(&self, _: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            tracing::span::Id::from_u64(1)
+        }
+        fn record(&self, _: &tracing::span::Id, _: &tracing::span::Record<'_>) {}
+        fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+        fn event(&self, _: &tracing::Event<'_>) {}
+        fn enter(&self, _: &tracing::span::Id) {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        }
+        fn exit(&self, _: &tracing::span::Id) {}
+    }
+
+    mod traced {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn tasks_run_inside_their_span() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        let entered = Arc::new(AtomicUsize::new(0));
+        let subscriber = tracing::Dispatch::new(Entered(entered.clone()));
+        let _default = tracing::dispatcher::set_default(&subscriber);
+        let session = traced::Session::activate().await;
+        session
+            .perform(async {
+                tokio::task::yield_now().await;
+                1
+            })
+            .await;
+        assert_eq!(session.take().await.unwrap(), 1);
+        assert!(entered.load(Ordering::SeqCst) >= 2);
+    }
+}
@@ -0,0 +1,673 @@
+use crate::progress::ProgressCell;
+use crate::time::Instant;
+use crate::{
+    MaybeSend, Perform, PerformError, PerformHandle, ProgressSink, Session, StoreProvider,
+    TaskMeta, ThrottleMode,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use futures::future::BoxFuture;
+#[cfg(target_arch = "wasm32")]
+use futures::future::LocalBoxFuture;
+use futures::FutureExt;
+use std::future::Future;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Default)]
+pub enum PerformerState {
+    #[default]
+    Idle,
+    InFlight {
+        since: Instant,
+    },
+    Ready {
+        since: Instant,
+        completed_at: Instant,
+    },
+    Failed {
+        error: PerformError,
+        at: Instant,
+    },
+}
+
+impl PerformerState {
+    pub fn is_idle(&self) -> bool {
+        matches!(self, Self::Idle)
+    }
+    pub fn is_in_flight(&self) -> bool {
+        matches!(self, Self::InFlight { .. })
+    }
+    pub fn is_ready(&self) -> bool {
+        matches!(self, Self::Ready { .. })
+    }
+    pub fn error(&self) -> Option<&PerformError> {
+        match self {
+            Self::Failed { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+type Factory<T> = Box<dyn FnMut() -> BoxFuture<'static, T> + Send>;
+#[cfg(target_arch = "wasm32")]
+type Factory<T> = Box<dyn FnMut() -> LocalBoxFuture<'static, T>>;
+#[cfg(not(target_arch = "wasm32"))]
+type RollbackHook = Box<dyn FnOnce(&PerformError) + Send>;
+#[cfg(target_arch = "wasm32")]
+type RollbackHook = Box<dyn FnOnce(&PerformError)>;
+
+#[cfg(not(target_arch = "wasm32"))]
+fn boxed<T, Fut: Future<Output = T> + Send + 'static>(fut: Fut) -> BoxFuture<'static, T> {
+    fut.boxed()
+}
+#[cfg(target_arch = "wasm32")]
+fn boxed<T, Fut: Future<Output = T> + 'static>(fut: Fut) -> LocalBoxFuture<'static, T> {
+    fut.boxed_local()
+}
+
+struct Rollback<T> {
+    previous: Option<T>,
+    hook: RollbackHook,
+}
+
+pub struct Performer<P: StoreProvider> {
+    session: Session<P>,
+    state: PerformerState,
+    in_flight: Option<PerformHandle>,
+    last_fired: Option<Instant>,
+    trailing: Option<PerformHandle>,
+    reported: ProgressCell,
+    factory: Option<Factory<P::Value>>,
+    cached: Option<P::Value>,
+    rollback: Option<Rollback<P::Value>>,
+    last_meta: Option<TaskMeta>,
+}
+impl<P: StoreProvider> Performer<P> {
+    pub fn new(session: Session<P>) -> Self {
+        Self {
+            session,
+            state: PerformerState::Idle,
+            in_flight: None,
+            last_fired: None,
+            trailing: None,
+            reported: ProgressCell::default(),
+            factory: None,
+            cached: None,
+            rollback: None,
+            last_meta: None,
+        }
+    }
+    pub fn session(&self) -> &Session<P> {
+        &self.session
+    }
+    pub fn into_session(self) -> Session<P> {
+        self.session
+    }
+    pub fn is_pending(&self) -> bool {
+        self.state.is_in_flight()
+    }
+    pub fn state(&self) -> PerformerState {
+        let PerformerState::InFlight { since } = self.state else {
+            return self.state.clone();
+        };
+        let Ok(store) = P::shared().store().try_lock() else {
+            return self.state.clone();
+        };
+        let id = self.session.id();
+        if !store.is_ready(id) {
+            return self.state.clone();
+        }
+        let completed_at = store
+            .meta(id)
+            .map_or_else(Instant::now, |meta| meta.completed_at);
+        match store.peek_error(id) {
+            Some(error) => PerformerState::Failed {
+                error,
+                at: completed_at,
+            },
+            None => PerformerState::Ready {
+                since,
+                completed_at,
+            },
+        }
+    }
+    fn is_settled(&self) -> bool {
+        matches!(
+            self.state,
+            PerformerState::Idle | PerformerState::Ready { .. }
+        )
+    }
+    fn trigger(&mut self) {
+        self.state = PerformerState::InFlight {
+            since: Instant::now(),
+        };
+    }
+    pub fn in_flight_since(&self) -> Option<Instant> {
+        match self.state {
+            PerformerState::InFlight { since } => Some(since),
+            _ => None,
+        }
+    }
+    pub fn last_meta(&self) -> Option<&TaskMeta> {
+        self.last_meta.as_ref()
+    }
+    pub fn attempts(&self) -> Option<u32> {
+        self.last_meta.map(|meta| meta.attempts)
+    }
+    pub fn last_duration(&self) -> Option<Duration> {
+        self.last_meta.map(|meta| meta.duration())
+    }
+    pub fn progress(&self) -> Option<f32> {
+        self.reported.get()
+    }
+    pub fn progress_as<Q: Clone + 'static>(&self) -> Option<Q> {
+        self.reported.get()
+    }
+    pub fn abort(&mut self) {
+        for handle in [self.in_flight.take(), self.trailing.take()]
+            .into_iter()
+            .flatten()
+        {
+            handle.abort();
+        }
+        self.state = PerformerState::Idle;
+    }
+    pub fn reset(&mut self) {
+        self.abort();
+        self.last_fired = None;
+        self.reported = ProgressCell::default();
+        let _stale = self.session.try_ready();
+    }
+    pub fn restart_with_spawn_local<F>(&mut self, fut: F)
+    where
+        F: Future<Output = P::Value> + 'static,
+    {
+        self.reset();
+        self.perform_latest_with_spawn_local(fut)
+    }
+    pub fn restart<F>(&mut self, fut: F)
+    where
+        F: Future<Output = P::Value> + 'static + MaybeSend,
+    {
+        self.reset();
+        self.perform_latest_spawned(fut)
+    }
+    pub fn try_take(&mut self) -> Result<P::Value, PerformError> {
+        let taken = match self.session.try_take_latest_entry() {
+            Ok((taken, meta)) => {
+                self.last_meta = meta.or(self.last_meta);
+                taken
+            }
+            Err(error) => Err(error),
+        };
+        match &taken {
+            Ok(_) => {
+                let completed_at = self
+                    .last_meta
+                    .map_or_else(Instant::now, |meta| meta.completed_at);
+                let since = match self.state {
+                    PerformerState::InFlight { since } => since,
+                    _ => self.last_meta.map_or(completed_at, |meta| meta.started_at),
+                };
+                self.state = PerformerState::Ready {
+                    since,
+                    completed_at,
+                };
+                self.in_flight = None;
+                self.rollback = None;
+            }
+            Err(PerformError::Empty | PerformError::Locked) => {}
+            Err(error) => {
+                self.state = PerformerState::Failed {
+                    error: error.clone(),
+                    at: Instant::now(),
+                };
+                self.in_flight = None;
+                if let Some(Rollback { previous, hook }) = self.rollback.take() {
+                    self.cached = previous;
+                    hook(error);
+                }
+            }
+        }
+        taken
+    }
+    pub fn set_factory<F, Fut>(&mut self, mut factory: F)
+    where
+        F: FnMut() -> Fut + 'static + MaybeSend,
+        Fut: Future<Output = P::Value> + 'static + MaybeSend,
+    {
+        self.factory = Some(Box::new(move || boxed(factory())));
+    }
+    pub fn cached(&self) -> Option<&P::Value> {
+        self.cached.as_ref()
+    }
+    pub fn set_cached(&mut self, value: Option<P::Value>) {
+        self.cached = value;
+    }
+    pub fn take_cached(&mut self) -> Option<P::Value> {
+        self.cached.take()
+    }
+    pub fn refresh(&mut self) -> bool {
+        let Some(factory) = self.factory.as_mut() else {
+            return false;
+        };
+        let fut = factory();
+        self.perform_latest_spawned(fut);
+        true
+    }
+    pub fn poll(&mut self) -> Option<&P::Value> {
+        if let Ok(value) = self.try_take() {
+            self.cached = Some(value);
+        }
+        if self.cached.is_none() && self.is_settled() {
+            self.refresh();
+        }
+        self.cached.as_ref()
+    }
+    pub fn optimistic<F, H>(&mut self, local: P::Value, fut: F, on_rollback: H)
+    where
+        F: Future<Output = Result<P::Value, PerformError>> + 'static + MaybeSend,
+        H: FnOnce(&PerformError) + 'static + MaybeSend,
+    {
+        self.stage_optimistic(local, Box::new(on_rollback));
+        self.in_flight = Some(self.session.perform_result_spawned(fut));
+    }
+    pub fn optimistic_with_spawn_local<F, H>(&mut self, local: P::Value, fut: F, on_rollback: H)
+    where
+        F: Future<Output = Result<P::Value, PerformError>> + 'static,
+        H: FnOnce(&PerformError) + 'static + MaybeSend,
+    {
+        self.stage_optimistic(local, Box::new(on_rollback));
+        self.in_flight = Some(self.session.perform_result_with_spawn_local(fut));
+    }
+    fn stage_optimistic(&mut self, local: P::Value, hook: RollbackHook) {
+        if let Some(previous) = self.in_flight.take() {
+            previous.abort();
+        }
+        let _is_ready = self.session.try_ready();
+        let previous = match self.rollback.take() {
+            Some(pending) => pending.previous,
+            None => self.cached.take(),
+        };
+        self.cached = Some(local);
+        self.rollback = Some(Rollback { previous, hook });
+        self.trigger();
+    }
+    pub async fn perform_one_time_or_not<F>(&mut self, fut: F)
+    where
+        F: Future<Output = P::Value> + 'static + Send,
+    {
+        if self.is_settled() {
+            self.session.perform(fut).await;
+            self.trigger();
+        }
+    }
+    pub fn perform_one_time_or_not_with_spawn_local<F>(&mut self, fut: F)
+    where
+        F: Future<Output = P::Value> + 'static,
+    {
+        if self.is_settled() {
+            let _is_ready = self.session.try_ready();
+            self.in_flight = Some(self.session.perform_with_spawn_local(fut));
+            self.trigger();
+        }
+    }
+    pub fn perform_one_time_or_not_spawned<F>(&mut self, fut: F)
+    where
+        F: Future<Output = P::Value> + 'static + MaybeSend,
+    {
+        if self.is_settled() {
+            let _is_ready = self.session.try_ready();
+            self.in_flight = Some(self.session.perform_spawned(fut));
+            self.trigger();
+        }
+    }
+    pub fn perform_latest_with_spawn_local<F>(&mut self, fut: F)
+    where
+        F: Future<Output = P::Value> + 'static,
+    {
+        if let Some(previous) = self.in_flight.take() {
+            previous.abort();
+        }
+        let _is_ready = self.session.try_ready();
+        self.in_flight = Some(self.session.perform_with_spawn_local(fut));
+        self.trigger();
+    }
+    pub fn perform_latest_spawned<F>(&mut self, fut: F)
+    where
+        F: Future<Output = P::Value> + 'static + MaybeSend,
+    {
+        if let Some(previous) = self.in_flight.take() {
+            previous.abort();
+        }
+        let _is_ready = self.session.try_ready();
+        self.in_flight = Some(self.session.perform_spawned(fut));
+        self.trigger();
+    }
+    pub fn perform_with_progress_with_spawn_local<F, Fut>(&mut self, f: F)
+    where
+        F: FnOnce(ProgressSink) -> Fut,
+        Fut: Future<Output = P::Value> + 'static,
+    {
+        let sink = self.progress_sink();
+        self.perform_latest_with_spawn_local(f(sink))
+    }
+    pub fn perform_with_progress_spawned<F, Fut>(&mut self, f: F)
+    where
+        F: FnOnce(ProgressSink) -> Fut,
+        Fut: Future<Output = P::Value> + 'static + MaybeSend,
+    {
+        let sink = self.progress_sink();
+        self.perform_latest_spawned(f(sink))
+    }
+    fn progress_sink(&mut self) -> ProgressSink {
+        let generation = self.session.generation() + 1;
+        self.reported.restart::<P>(*self.session.id(), generation)
+    }
+
+    pub fn perform_debounced<F, Fut>(&mut self, factory: F, quiet: Duration)
+    where
+        F: FnOnce() -> Fut + 'static + MaybeSend,
+        Fut: Future<Output = P::Value> + 'static + MaybeSend,
+    {
+        self.perform_latest_spawned(async move {
+            crate::time::sleep(quiet).await;
+            factory().await
+        })
+    }
+
+    pub fn perform_throttled<F, Fut>(&mut self, factory: F, interval: Duration, mode: ThrottleMode)
+    where
+        F: FnOnce() -> Fut + 'static + MaybeSend,
+        Fut: Future<Output = P::Value> + 'static + MaybeSend,
+    {
+        match self.throttle(interval, mode) {
+            Some(delay) if delay.is_zero() => self.perform_latest_spawned(factory()),
+            Some(delay) => {
+                let _is_ready = self.session.try_ready();
+                let handle = self.session.perform_spawned(async move {
+                    crate::time::sleep(delay).await;
+                    factory().await
+                });
+                self.replace_trailing(handle);
+            }
+            None => {}
+        }
+    }
+    fn replace_trailing(&mut self, handle: PerformHandle) {
+        if let Some(previous) = self.trailing.replace(handle) {
+            previous.abort();
+        }
+        self.trigger();
+    }
+    fn throttle(&mut self, interval: Duration, mode: ThrottleMode) -> Option<Duration> {
+        let now = Instant::now();
+        let last_fired = match self.last_fired {
+            Some(last_fired) if last_fired + interval > now => last_fired,
+            _ => {
+                self.last_fired = Some(now);
+                return Some(Duration::ZERO);
+            }
+        };
+        match mode {
+            ThrottleMode::Drop => None,
+            ThrottleMode::Coalesce if last_fired > now => Some(last_fired - now),
+            ThrottleMode::Coalesce => {
+                self.last_fired = Some(last_fired + interval);
+                Some(last_fired + interval - now)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    mod debounced {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn perform_debounced_runs_only_the_last_trigger() {
+        use crate::Perform;
+        let mut performer = debounced::Performer::new(debounced::Session::activate().await);
+        let calls = Arc::new(AtomicU32::new(0));
+        for value in 1..=3 {
+            let calls = calls.clone();
+            let factory = move || async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                value
+            };
+            performer.perform_debounced(factory, Duration::from_millis(20));
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert!(performer.try_take().is_err());
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(performer.try_take().unwrap(), 3);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    mod reported {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn progress_is_readable_while_the_task_runs() {
+        use crate::Perform;
+        let mut performer = reported::Performer::new(reported::Session::activate().await);
+        assert_eq!(performer.progress(), None);
+        performer.perform_with_progress_spawned(|sink| async move {
+            sink.report(0.4);
+            tokio::time::sleep(Duration::from_millis(30)).await;
+            sink.report_value("saving");
+            tokio::time::sleep(Duration::from_millis(30)).await;
+            7
+        });
+        tokio::time::sleep(Duration::from_millis(15)).await;
+        assert_eq!(performer.progress(), Some(0.4));
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert_eq!(performer.progress_as::<&str>(), Some("saving"));
+        assert!(performer.try_take().is_err());
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        assert_eq!(performer.try_take().unwrap(), 7);
+        assert_eq!(performer.progress_as::<&str>(), Some("saving"));
+    }
+
+    mod stateful {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn state_distinguishes_idle_in_flight_ready_and_failed() {
+        use crate::{Perform, PerformerState};
+        let mut performer = stateful::Performer::new(stateful::Session::activate().await);
+        assert!(performer.state().is_idle());
+
+        performer.perform_one_time_or_not_spawned(async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            1
+        });
+        assert!(performer.state().is_in_flight());
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        match performer.state() {
+            PerformerState::Ready {
+                since,
+                completed_at,
+            } => assert!(completed_at > since),
+            state => panic!("unexpected {state:?}"),
+        }
+        assert_eq!(performer.try_take().unwrap(), 1);
+        assert!(performer.state().is_ready());
+        assert!(performer.try_take().is_err());
+        assert!(performer.state().is_ready());
+
+        performer.perform_one_time_or_not_spawned(async { 2 });
+        assert!(!performer.state().is_ready());
+        performer.abort();
+        assert!(performer.state().is_idle());
+    }
+
+    mod failing {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn failed_state_sticks_until_reset() {
+        use crate::{Perform, PerformConfig, PerformError, StoreProvider};
+        failing::Provider::configure(PerformConfig {
+            timeout: Some(Duration::from_millis(10)),
+            ..Default::default()
+        })
+        .await;
+        let mut performer = failing::Performer::new(failing::Session::activate().await);
+        performer.perform_one_time_or_not_spawned(async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            1
+        });
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert!(matches!(
+            performer.state().error(),
+            Some(PerformError::TimedOut)
+        ));
+        assert!(performer.try_take().is_err());
+        assert!(matches!(
+            performer.state().error(),
+            Some(PerformError::TimedOut)
+        ));
+
+        performer.reset();
+        assert!(performer.state().is_idle());
+        performer.perform_one_time_or_not_spawned(async { 2 });
+        performer.restart(async { 3 });
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        assert_eq!(performer.try_take().unwrap(), 3);
+        assert!(matches!(performer.try_take(), Err(PerformError::Empty)));
+    }
+
+    mod throttled {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn perform_throttled_coalesces_or_drops_intermediate_triggers() {
+        use crate::{Perform, ThrottleMode};
+        for mode in [ThrottleMode::Coalesce, ThrottleMode::Drop] {
+            let mut performer = throttled::Performer::new(throttled::Session::activate().await);
+            let calls = Arc::new(AtomicU32::new(0));
+            for value in 1..=3 {
+                let calls = calls.clone();
+                let factory = move || async move {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    value
+                };
+                performer.perform_throttled(factory, Duration::from_millis(30), mode);
+            }
+            tokio::time::sleep(Duration::from_millis(60)).await;
+            match mode {
+                ThrottleMode::Coalesce => {
+                    assert_eq!(performer.try_take().unwrap(), 3);
+                    assert_eq!(calls.load(Ordering::SeqCst), 2);
+                }
+                ThrottleMode::Drop => {
+                    assert_eq!(performer.try_take().unwrap(), 1);
+                    assert_eq!(calls.load(Ordering::SeqCst), 1);
+                }
+            }
+        }
+    }
+
+    mod optimistic {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn optimistic_value_is_replaced_or_rolled_back() {
+        use crate::{Perform, PerformError};
+        let mut performer = optimistic::Performer::new(optimistic::Session::activate().await);
+        let rolled_back = Arc::new(AtomicU32::new(0));
+
+        let counter = rolled_back.clone();
+        performer.optimistic(1, async { Ok(2) }, move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+        assert_eq!(performer.cached(), Some(&1));
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        assert_eq!(performer.poll(), Some(&2));
+
+        let counter = rolled_back.clone();
+        performer.optimistic(3, async { Err(PerformError::TimedOut) }, move |error| {
+            assert!(matches!(error, PerformError::TimedOut));
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+        assert_eq!(performer.cached(), Some(&3));
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        assert_eq!(performer.poll(), Some(&2));
+        assert_eq!(rolled_back.load(Ordering::SeqCst), 1);
+        assert!(performer.state().error().is_some());
+    }
+
+    mod timed {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn timing_comes_from_the_last_taken_task() {
+        use crate::Perform;
+        let mut performer = timed::Performer::new(timed::Session::activate().await);
+        assert_eq!(performer.attempts(), None);
+        performer.perform_latest_spawned(async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            1
+        });
+        assert!(performer.in_flight_since().is_some());
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        assert_eq!(performer.try_take().unwrap(), 1);
+        assert_eq!(performer.in_flight_since(), None);
+        assert_eq!(performer.attempts(), Some(1));
+        assert!(performer.last_duration().unwrap() >= Duration::from_millis(20));
+    }
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    #[cfg(target_arch = "wasm32")]
+    async fn performer_flows_from_in_flight_to_cached_on_wasm() {
+        use crate::LocalPerform;
+        let mut performer = debounced::Performer::new(debounced::Session::activate().await);
+        assert!(performer.state().is_idle());
+        performer.restart(async {
+            crate::time::sleep(Duration::from_millis(10)).await;
+            1
+        });
+        assert!(performer.is_pending());
+        assert!(performer.state().is_in_flight());
+        performer.restart(async { 2 });
+        let taken = crate::tests::polled(|| performer.try_take()).await;
+        assert_eq!(taken.unwrap(), 2);
+        assert!(performer.state().is_ready());
+
+        let calls = Arc::new(AtomicU32::new(0));
+        let counted = calls.clone();
+        performer.set_factory(move || {
+            let calls = counted.clone();
+            async move { calls.fetch_add(1, Ordering::SeqCst) + 10 }
+        });
+        assert!(performer.poll().is_none());
+        for _ in 0..200 {
+            if performer.poll().is_some() {
+                break;
+            }
+            crate::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert_eq!(performer.cached(), Some(&10));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,122 @@
+use crate::{MaybeSend, Perform, PerformError, Performer, Session, StoreProvider};
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::Hash;
+
+pub struct PerformerMap<K, P: StoreProvider> {
+    performers: HashMap<K, Performer<P>>,
+}
+impl<K: Eq + Hash, P: StoreProvider> PerformerMap<K, P> {
+    pub fn new() -> Self {
+        Self {
+            performers: HashMap::new(),
+        }
+    }
+    pub fn len(&self) -> usize {
+        self.performers.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.performers.is_empty()
+    }
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.performers.contains_key(key)
+    }
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.performers.keys()
+    }
+    pub fn get(&self, key: &K) -> Option<&Performer<P>> {
+        self.performers.get(key)
+    }
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut Performer<P>> {
+        self.performers.get_mut(key)
+    }
+    pub fn is_pending(&self, key: &K) -> bool {
+        self.performers
+            .get(key)
+            .is_some_and(|performer| performer.is_pending())
+    }
+    pub fn try_take(&mut self, key: &K) -> Result<P::Value, PerformError> {
+        match self.performers.get_mut(key) {
+            Some(performer) => performer.try_take(),
+            None => Err(PerformError::Empty),
+        }
+    }
+    pub fn get_or_spawn<F, Fut>(&mut self, key: K, factory: F) -> &mut Performer<P>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = P::Value> + 'static + MaybeSend,
+    {
+        self.performers.entry(key).or_insert_with(|| {
+            let mut performer = Performer::new(Session::try_activate());
+            performer.perform_one_time_or_not_spawned(factory());
+            performer
+        })
+    }
+    pub fn insert(&mut self, key: K, performer: Performer<P>) -> Option<Performer<P>> {
+        self.performers.insert(key, performer).map(|mut previous| {
+            previous.abort();
+            previous
+        })
+    }
+    pub fn remove(&mut self, key: &K) -> Option<Performer<P>> {
+        self.performers.remove(key).map(|mut performer| {
+            performer.abort();
+            performer
+        })
+    }
+    pub fn retain_present<I>(&mut self, present: I) -> usize
+    where
+        I: IntoIterator<Item = K>,
+    {
+        let present: std::collections::HashSet<K> = present.into_iter().collect();
+        let before = self.performers.len();
+        self.performers.retain(|key, performer| {
+            let keep = present.contains(key);
+            if !keep {
+                performer.abort();
+            }
+            keep
+        });
+        before - self.performers.len()
+    }
+}
+impl<K: Eq + Hash, P: StoreProvider> Default for PerformerMap<K, P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use std::time::Duration;
+
+    mod rows {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    async fn get_or_spawn_runs_once_per_key_and_drops_absent_keys() {
+        let mut map = crate::PerformerMap::<u32, rows::Provider>::new();
+        for _ in 0..2 {
+            for row in [1, 2, 3] {
+                map.get_or_spawn(row, move || async move {
+                    tokio::time::sleep(Duration::from_millis(10 * row as u64)).await;
+                    row * 10
+                });
+            }
+        }
+        assert_eq!(map.len(), 3);
+        assert!(map.is_pending(&3));
+
+        tokio::time::sleep(Duration::from_millis(15)).await;
+        assert_eq!(map.try_take(&1).unwrap(), 10);
+        assert!(!map.is_pending(&1));
+        assert!(map.try_take(&3).is_err());
+
+        assert_eq!(map.retain_present([1, 2]), 1);
+        assert!(!map.contains_key(&3));
+        assert!(map.try_take(&3).is_err());
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(map.try_take(&2).unwrap(), 20);
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{Completion, Perform, PerformError, Session, StoreProvider, Uuid};
+use futures::future::{AbortHandle, Abortable};
+use futures::StreamExt;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::rc::Rc;
+use web_sys::Storage;
+
+type Bound = Rc<RefCell<HashMap<Uuid, String>>>;
+
+fn encode<T: Serialize>(value: &T) -> Option<String> {
+    let json = serde_wasm_bindgen::to_value(value).ok()?;
+    js_sys::JSON::stringify(&json).ok()?.as_string()
+}
+
+fn decode<T: DeserializeOwned>(text: &str) -> Option<T> {
+    let json = js_sys::JSON::parse(text).ok()?;
+    serde_wasm_bindgen::from_value(json).ok()
+}
+
+pub struct LocalPersist<P: StoreProvider> {
+    storage: Storage,
+    namespace: String,
+    bound: Bound,
+    writer: AbortHandle,
+    provider: PhantomData<P>,
+}
+
+impl<P: StoreProvider> LocalPersist<P>
+where
+    P::Value: Serialize + DeserializeOwned + Clone,
+{
+    pub fn open(namespace: &str) -> Result<Self, PerformError> {
+        let storage = web_sys::window()
+            .ok_or(PerformError::Closed)?
+            .local_storage()?
+            .ok_or(PerformError::Closed)?;
+        let bound = Bound::default();
+
+        let (writer, registration) = AbortHandle::new_pair();
+        let (target, outgoing) = (storage.clone(), bound.clone());
+        let namespace = namespace.to_string();
+        let keys = namespace.clone();
+        let completions = P::shared().completions();
+        crate::spawn_local(async move {
+            let written = completions.for_each(move |Completion { id, result, .. }| {
+                let key = outgoing.borrow().get(&id).cloned();
+                if let (Some(key), Ok(value)) = (key, result) {
+                    if let Some(text) = encode(&value) {
+                        let _ = target.set_item(&Self::storage_key(&keys, &key), &text);
+                    }
+                }
+                futures::future::ready(())
+            });
+            let _ = Abortable::new(written, registration).await;
+        });
+
+        Ok(Self {
+            storage,
+            namespace,
+            bound,
+            writer,
+            provider: PhantomData,
+        })
+    }
+
+    fn storage_key(namespace: &str, key: &str) -> String {
+        format!("{namespace}:{key}")
+    }
+
+    pub fn load(&self, key: &str) -> Option<P::Value> {
+        let text = self
+            .storage
+            .get_item(&Self::storage_key(&self.namespace, key))
+            .ok()??;
+        decode(&text)
+    }
+
+    pub fn forget(&self, key: &str) -> Result<(), PerformError> {
+        let key = Self::storage_key(&self.namespace, key);
+        self.storage.remove_item(&key).map_err(PerformError::from)
+    }
+
+    pub async fn bind(&self, session: &Session<P>, key: impl Into<String>) -> bool {
+        let key = key.into();
+        let stored = self.load(&key);
+        self.bound.borrow_mut().insert(*session.id(), key);
+        match stored {
+            Some(value) => session.hydrate(value).await.is_ok(),
+            None => false,
+        }
+    }
+
+    pub fn unbind(&self, session: &Session<P>) -> bool {
+        self.bound.borrow_mut().remove(session.id()).is_some()
+    }
+}
+
+impl<P: StoreProvider> Drop for LocalPersist<P> {
+    fn drop(&mut self) {
+        self.writer.abort();
+    }
+}
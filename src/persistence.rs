@@ -0,0 +1,65 @@
+use crate::{Performer, StoreProvider};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Persisted<T> {
+    value: Option<T>,
+    ready: bool,
+}
+
+impl<P: StoreProvider> Performer<P>
+where
+    P::Value: Clone + Serialize + DeserializeOwned + Sync,
+{
+    pub fn save(&self, ctx: &egui::Context, id: egui::Id) {
+        let persisted = Persisted {
+            value: self.cached().cloned(),
+            ready: self.state().is_ready() || self.cached().is_some(),
+        };
+        ctx.data().insert_persisted(id, persisted);
+    }
+    pub fn load(&mut self, ctx: &egui::Context, id: egui::Id) -> bool {
+        let persisted = ctx.data().get_persisted::<Persisted<P::Value>>(id);
+        match persisted {
+            Some(Persisted {
+                value: Some(value),
+                ready: true,
+            }) => {
+                self.set_cached(Some(value));
+                self.refresh();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use crate::Perform;
+    use std::time::Duration;
+
+    mod persisted {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    async fn loaded_value_renders_while_a_refresh_runs() {
+        let ctx = egui::Context::default();
+        let id = egui::Id::new("persisted");
+        let mut before = persisted::Performer::new(persisted::Session::activate().await);
+        before.perform_latest_spawned(async { 1 });
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        assert_eq!(before.poll(), Some(&1));
+        before.save(&ctx, id);
+
+        let mut after = persisted::Performer::new(persisted::Session::activate().await);
+        after.set_factory(|| async { 2 });
+        assert!(after.load(&ctx, id));
+        assert_eq!(after.cached(), Some(&1));
+        assert!(after.is_pending());
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        assert_eq!(after.poll(), Some(&2));
+    }
+}
@@ -0,0 +1,122 @@
+use crate::{Perform, PerformError, Performer, PerformerMap, Priority, Session, StoreProvider};
+use std::future::Future;
+use std::ops::Range;
+
+pub struct Prefetcher<P: StoreProvider> {
+    rows: PerformerMap<usize, P>,
+    ahead: usize,
+    keep: usize,
+}
+
+impl<P: StoreProvider> Prefetcher<P> {
+    pub fn new(ahead: usize, keep: usize) -> Self {
+        Self {
+            rows: PerformerMap::new(),
+            ahead,
+            keep: keep.max(ahead),
+        }
+    }
+    pub fn ahead(&self) -> usize {
+        self.ahead
+    }
+    pub fn keep(&self) -> usize {
+        self.keep
+    }
+    pub fn rows(&self) -> &PerformerMap<usize, P> {
+        &self.rows
+    }
+    pub fn get(&self, row: usize) -> Option<&Performer<P>> {
+        self.rows.get(&row)
+    }
+    pub fn is_pending(&self, row: usize) -> bool {
+        self.rows.is_pending(&row)
+    }
+    pub fn try_take(&mut self, row: usize) -> Result<P::Value, PerformError> {
+        self.rows.try_take(&row)
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn update<F, Fut>(&mut self, visible: Range<usize>, total: usize, mut factory: F) -> usize
+    where
+        F: FnMut(usize) -> Fut,
+        Fut: Future<Output = P::Value> + 'static + Send,
+    {
+        for (row, priority) in self.wanted(&visible, total) {
+            if !self.rows.contains_key(&row) {
+                let mut performer = Performer::new(session::<P>(priority));
+                performer.perform_one_time_or_not_spawned(factory(row));
+                self.rows.insert(row, performer);
+            }
+        }
+        self.evict(&visible)
+    }
+    #[cfg(target_arch = "wasm32")]
+    pub fn update<F, Fut>(&mut self, visible: Range<usize>, total: usize, mut factory: F) -> usize
+    where
+        F: FnMut(usize) -> Fut,
+        Fut: Future<Output = P::Value> + 'static,
+    {
+        for (row, priority) in self.wanted(&visible, total) {
+            if !self.rows.contains_key(&row) {
+                let mut performer = Performer::new(session::<P>(priority));
+                performer.perform_one_time_or_not_spawned(factory(row));
+                self.rows.insert(row, performer);
+            }
+        }
+        self.evict(&visible)
+    }
+    fn wanted(&self, visible: &Range<usize>, total: usize) -> Vec<(usize, Priority)> {
+        let end = visible.end.min(total);
+        let start = visible.start.min(end);
+        let before = start.saturating_sub(self.ahead)..start;
+        let after = end..(end + self.ahead).min(total);
+        (start..end)
+            .map(|row| (row, Priority::Normal))
+            .chain(after.chain(before.rev()).map(|row| (row, Priority::Low)))
+            .collect()
+    }
+    fn evict(&mut self, visible: &Range<usize>) -> usize {
+        let kept = visible.start.saturating_sub(self.keep)..visible.end.saturating_add(self.keep);
+        let present: Vec<usize> = self
+            .rows
+            .keys()
+            .copied()
+            .filter(|row| kept.contains(row))
+            .collect();
+        self.rows.retain_present(present)
+    }
+}
+
+fn session<P: StoreProvider>(priority: Priority) -> Session<P> {
+    let mut session = Session::try_activate();
+    session.set_priority(priority);
+    session
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::Prefetcher;
+    use crate::Priority;
+
+    mod rows {
+        crate::build_perform!(usize);
+    }
+
+    #[tokio::test]
+    async fn rows_around_the_viewport_are_prefetched_and_far_ones_dropped() {
+        let mut prefetcher = Prefetcher::<rows::Provider>::new(2, 4);
+        let fetch = |row: usize| async move { row };
+        assert_eq!(prefetcher.update(10..13, 100, fetch), 0);
+        let mut fetched: Vec<usize> = prefetcher.rows().keys().copied().collect();
+        fetched.sort();
+        assert_eq!(fetched, vec![8, 9, 10, 11, 12, 13, 14]);
+        let priority = |row| prefetcher.get(row).unwrap().session().priority();
+        assert_eq!(priority(11), Priority::Normal);
+        assert_eq!(priority(14), Priority::Low);
+
+        assert_eq!(prefetcher.update(20..23, 100, fetch), 7);
+        assert!(prefetcher.get(9).is_none());
+        assert_eq!(prefetcher.rows().len(), 7);
+        assert_eq!(prefetcher.update(98..100, 100, fetch), 7);
+        assert_eq!(prefetcher.rows().len(), 4);
+    }
+}
@@ -0,0 +1,54 @@
+use crate::lifecycle::{self, Lifecycle};
+use crate::{StoreProvider, Uuid};
+use std::any::Any;
+use std::sync::{Arc, Mutex};
+
+type Latest = Arc<Mutex<Option<Box<dyn Any + Send>>>>;
+
+#[derive(Clone)]
+pub struct ProgressSink {
+    latest: Latest,
+    store: &'static str,
+    id: Uuid,
+    generation: u64,
+}
+impl ProgressSink {
+    pub(crate) fn new<P: StoreProvider>(latest: Latest, id: Uuid, generation: u64) -> Self {
+        Self {
+            latest,
+            store: std::any::type_name::<P>(),
+            id,
+            generation,
+        }
+    }
+    pub fn report(&self, fraction: f32) {
+        let fraction = fraction.clamp(0.0, 1.0);
+        self.report_value(fraction);
+        let progress = Lifecycle::Progress(fraction);
+        lifecycle::emit_from(self.store, self.id, self.generation, progress);
+    }
+    pub fn report_value<Q: Send + 'static>(&self, value: Q) {
+        *self
+            .latest
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(Box::new(value));
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct ProgressCell {
+    latest: Latest,
+}
+impl ProgressCell {
+    pub(crate) fn restart<P: StoreProvider>(&mut self, id: Uuid, generation: u64) -> ProgressSink {
+        self.latest = Latest::default();
+        ProgressSink::new::<P>(self.latest.clone(), id, generation)
+    }
+    pub(crate) fn get<Q: Clone + 'static>(&self) -> Option<Q> {
+        let latest = self
+            .latest
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        latest.as_ref()?.downcast_ref::<Q>().cloned()
+    }
+}
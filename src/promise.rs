@@ -0,0 +1,87 @@
+use crate::{Perform, PerformError, Performer, Session, StoreProvider};
+use poll_promise::Promise;
+use std::time::Duration;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(16);
+
+impl<P: StoreProvider> From<Session<P>> for Promise<Result<P::Value, PerformError>> {
+    fn from(session: Session<P>) -> Self {
+        let (sender, promise) = Promise::new();
+        crate::spawn_portable(async move { sender.send(settled(&session).await) });
+        promise
+    }
+}
+
+impl<P: StoreProvider> From<Performer<P>> for Promise<Result<P::Value, PerformError>> {
+    fn from(performer: Performer<P>) -> Self {
+        performer.into_session().into()
+    }
+}
+
+impl<P: StoreProvider> From<Promise<P::Value>> for Session<P> {
+    fn from(promise: Promise<P::Value>) -> Self {
+        let session = Session::try_activate();
+        session.perform_spawned(resolved(promise));
+        session
+    }
+}
+
+impl<P: StoreProvider> From<Promise<P::Value>> for Performer<P> {
+    fn from(promise: Promise<P::Value>) -> Self {
+        let mut performer = Performer::new(Session::try_activate());
+        performer.perform_latest_spawned(resolved(promise));
+        performer
+    }
+}
+
+async fn settled<P: StoreProvider>(session: &Session<P>) -> Result<P::Value, PerformError> {
+    loop {
+        let completed = P::shared().completed();
+        match session.take().await {
+            Err(PerformError::Empty) => {}
+            result => return result,
+        }
+        completed.await;
+    }
+}
+
+async fn resolved<T: Send + 'static>(mut promise: Promise<T>) -> T {
+    loop {
+        match promise.try_take() {
+            Ok(value) => return value,
+            Err(pending) => promise = pending,
+        }
+        crate::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use crate::Perform;
+    use poll_promise::Promise;
+    use std::time::Duration;
+
+    mod promised {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    async fn promises_convert_both_ways() {
+        let (sender, promise) = Promise::new();
+        let session: promised::Session = promise.into();
+        sender.send(4);
+        let taken = session.try_take_timeout(Duration::from_secs(1)).await;
+        assert_eq!(taken.unwrap(), 4);
+
+        let session = promised::Session::activate().await;
+        session.perform_spawned(async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            5
+        });
+        let promise: Promise<_> = session.into();
+        while promise.ready().is_none() {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert!(matches!(promise.ready(), Some(Ok(5))));
+    }
+}
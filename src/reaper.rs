@@ -0,0 +1,84 @@
+use crate::store::lock_and_do_mut;
+use crate::StoreProvider;
+use futures::future::{AbortHandle, Abortable};
+use futures::StreamExt;
+use std::time::Duration;
+
+pub struct Reaper {
+    handle: AbortHandle,
+}
+
+impl Reaper {
+    pub fn spawn<P: StoreProvider>(interval: Duration) -> Self {
+        let (handle, registration) = AbortHandle::new_pair();
+        let reaping = async move {
+            let ticks = crate::time::interval(interval).skip(1);
+            futures::pin_mut!(ticks);
+            while ticks.next().await.is_some() {
+                let reaped = lock_and_do_mut(P::shared(), |store| store.sweep()).await;
+                if reaped > 0 {
+                    log::debug!("reaped {} entries", reaped);
+                }
+            }
+        };
+        crate::spawn_portable(async move {
+            let _ = Abortable::new(reaping, registration).await;
+        });
+        Self { handle }
+    }
+
+    pub fn stop(&self) {
+        self.handle.abort();
+    }
+    pub fn is_stopped(&self) -> bool {
+        self.handle.is_aborted()
+    }
+}
+
+impl Drop for Reaper {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::Reaper;
+    use crate::{Perform, StoreProvider};
+    use std::time::Duration;
+
+    mod reaped {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    async fn dropped_sessions_are_swept() {
+        let reaper = Reaper::spawn::<reaped::Provider>(Duration::from_millis(5));
+        let kept = reaped::Session::activate().await;
+        let dropped = reaped::Session::activate().await;
+        dropped.perform(async { 1 }).await;
+        drop(dropped);
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        reaper.stop();
+        let store = reaped::Provider::store().lock().await;
+        assert_eq!(store.len(), 1);
+        assert!(store.contains(kept.id()));
+    }
+
+    mod abandoned {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    async fn dropping_the_reaper_stops_it() {
+        let reaper = Reaper::spawn::<abandoned::Provider>(Duration::from_millis(5));
+        drop(reaper);
+        let dropped = abandoned::Session::activate().await;
+        dropped.perform(async { 1 }).await;
+        drop(dropped);
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(abandoned::Provider::store().lock().await.len(), 1);
+    }
+}
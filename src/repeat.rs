@@ -0,0 +1,148 @@
+use crate::session::{enter, gated, Dispatch};
+use crate::time::Instant;
+use crate::{
+    InsertOptions, Perform, PerformError, PerformHandle, Priority, Session, StoreProvider, Uuid,
+};
+use futures::FutureExt;
+use std::future::Future;
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct Repeating {
+    handle: PerformHandle,
+}
+impl Repeating {
+    pub fn stop(&self) {
+        self.handle.abort()
+    }
+    pub fn is_stopped(&self) -> bool {
+        self.handle.is_finished()
+    }
+    pub fn handle(&self) -> &PerformHandle {
+        &self.handle
+    }
+}
+
+impl<P: StoreProvider> Session<P> {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn perform_every<F, Fut>(&self, period: Duration, factory: F) -> Repeating
+    where
+        F: FnMut() -> Fut + 'static + Send,
+        Fut: Future<Output = P::Value> + 'static + Send,
+        P::Value: Send,
+    {
+        let (id, priority) = (*self.id(), self.priority());
+        let handle = self.spawn_staged(
+            move |options| every::<P, _, _>(id, priority, period, factory, options),
+            Dispatch::ungated(),
+        );
+        Repeating { handle }
+    }
+    #[cfg(target_arch = "wasm32")]
+    pub fn perform_every<F, Fut>(&self, period: Duration, factory: F) -> Repeating
+    where
+        F: FnMut() -> Fut + 'static,
+        Fut: Future<Output = P::Value> + 'static,
+    {
+        self.perform_every_with_spawn_local(period, factory)
+    }
+    pub fn perform_every_with_spawn_local<F, Fut>(&self, period: Duration, factory: F) -> Repeating
+    where
+        F: FnMut() -> Fut + 'static,
+        Fut: Future<Output = P::Value> + 'static,
+    {
+        let (id, priority) = (*self.id(), self.priority());
+        let handle = self.spawn_staged_with_spawn_local(
+            move |options| every::<P, _, _>(id, priority, period, factory, options),
+            Dispatch::ungated(),
+        );
+        Repeating { handle }
+    }
+}
+
+async fn every<P, F, Fut>(
+    id: Uuid,
+    priority: Priority,
+    period: Duration,
+    mut factory: F,
+    options: InsertOptions,
+) -> (Result<P::Value, PerformError>, u32)
+where
+    P: StoreProvider,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = P::Value>,
+{
+    let mut next = Instant::now();
+    loop {
+        let Ok(active) = enter::<P>(false) else {
+            return (Err(PerformError::Closed), 0);
+        };
+        let tick = factory().map(|value| (Ok(value), 1));
+        let dispatch = Dispatch::prioritized(priority);
+        if let Some((result, _)) = gated::<P, _>(id, options.generation, dispatch, tick).await {
+            P::shared().settle_each([(id, options, result)]).await;
+        }
+        drop(active);
+        next += period;
+        crate::time::sleep_until(next).await;
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use crate::Perform;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    mod polled {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    async fn perform_every_keeps_the_freshest_result_until_stopped() {
+        let session = polled::Session::activate().await;
+        let runs = Arc::new(AtomicU32::new(0));
+        let counter = runs.clone();
+        let repeating = session.perform_every(Duration::from_millis(20), move || {
+            let run = counter.fetch_add(1, Ordering::SeqCst) + 1;
+            async move { run }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let freshest = session.try_take().unwrap();
+        assert!(freshest >= 2);
+        assert_eq!(freshest, runs.load(Ordering::SeqCst));
+
+        repeating.stop();
+        repeating.handle().clone().await;
+        assert!(repeating.is_stopped());
+        let stopped_at = runs.load(Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(runs.load(Ordering::SeqCst), stopped_at);
+    }
+
+    mod limited {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    async fn each_tick_is_admitted_and_timed_on_its_own() {
+        use crate::{PerformConfig, StoreProvider};
+        limited::Provider::configure(PerformConfig {
+            timeout: Some(Duration::from_millis(30)),
+            max_in_flight: Some(1),
+            ..Default::default()
+        })
+        .await;
+        let ticking = limited::Session::activate().await;
+        let repeating = ticking.perform_every(Duration::from_millis(10), || async { 1 });
+        let other = limited::Session::activate().await;
+        tokio::time::timeout(Duration::from_secs(1), other.perform(async { 2 }))
+            .await
+            .unwrap();
+        assert_eq!(other.take().await.unwrap(), 2);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(ticking.try_take().unwrap(), 1);
+        repeating.stop();
+    }
+}
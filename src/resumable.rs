@@ -0,0 +1,251 @@
+use crate::{PerformError, RemoteError, Session, StoreProvider, Uuid};
+#[cfg(not(target_arch = "wasm32"))]
+use futures::future::BoxFuture;
+#[cfg(target_arch = "wasm32")]
+use futures::future::LocalBoxFuture;
+use futures::FutureExt;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskDescriptor {
+    pub id: Uuid,
+    pub name: String,
+    pub params: String,
+}
+
+pub trait Journal: Send + Sync {
+    fn load(&self) -> Vec<TaskDescriptor>;
+    fn save(&self, pending: &[TaskDescriptor]);
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MemoryJournal(Arc<Mutex<Vec<TaskDescriptor>>>);
+
+impl Journal for MemoryJournal {
+    fn load(&self) -> Vec<TaskDescriptor> {
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+    fn save(&self, pending: &[TaskDescriptor]) {
+        *self
+            .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = pending.to_vec();
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", feature = "persist"))]
+#[derive(Debug, Clone)]
+pub struct LocalStorageJournal {
+    key: String,
+}
+
+#[cfg(all(target_arch = "wasm32", feature = "persist"))]
+impl LocalStorageJournal {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self { key: key.into() }
+    }
+
+    fn storage() -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok()?
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", feature = "persist"))]
+impl Journal for LocalStorageJournal {
+    fn load(&self) -> Vec<TaskDescriptor> {
+        Self::storage()
+            .and_then(|storage| storage.get_item(&self.key).ok()?)
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+    fn save(&self, pending: &[TaskDescriptor]) {
+        if let (Some(storage), Ok(text)) = (Self::storage(), serde_json::to_string(pending)) {
+            let _ = storage.set_item(&self.key, &text);
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+type Job<T> = BoxFuture<'static, Result<T, PerformError>>;
+#[cfg(target_arch = "wasm32")]
+type Job<T> = LocalBoxFuture<'static, Result<T, PerformError>>;
+type Launcher<T> = Box<dyn Fn(&str) -> Result<Job<T>, PerformError>>;
+type Pending = Arc<Mutex<Vec<TaskDescriptor>>>;
+
+pub struct ResumableRegistry<P: StoreProvider> {
+    journal: Arc<dyn Journal>,
+    pending: Pending,
+    launchers: HashMap<String, Launcher<P::Value>>,
+    jobs: HashMap<Uuid, Session<P>>,
+}
+
+impl<P: StoreProvider> ResumableRegistry<P> {
+    pub fn new<J: Journal + 'static>(journal: J) -> Self {
+        let pending = journal.load();
+        Self {
+            journal: Arc::new(journal),
+            pending: Arc::new(Mutex::new(pending)),
+            launchers: HashMap::new(),
+            jobs: HashMap::new(),
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn register<T, F, Fut>(&mut self, name: &str, factory: F)
+    where
+        T: DeserializeOwned,
+        F: Fn(T) -> Fut + 'static,
+        Fut: Future<Output = Result<P::Value, PerformError>> + 'static,
+    {
+        let launcher = move |params: &str| {
+            let params = serde_json::from_str(params).map_err(PerformError::task)?;
+            Ok(factory(params).boxed_local())
+        };
+        self.launchers.insert(name.to_string(), Box::new(launcher));
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn register<T, F, Fut>(&mut self, name: &str, factory: F)
+    where
+        T: DeserializeOwned,
+        F: Fn(T) -> Fut + 'static,
+        Fut: Future<Output = Result<P::Value, PerformError>> + 'static + Send,
+    {
+        let launcher = move |params: &str| {
+            let params = serde_json::from_str(params).map_err(PerformError::task)?;
+            Ok(factory(params).boxed())
+        };
+        self.launchers.insert(name.to_string(), Box::new(launcher));
+    }
+
+    fn lock(pending: &Pending) -> std::sync::MutexGuard<'_, Vec<TaskDescriptor>> {
+        pending
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn pending(&self) -> Vec<TaskDescriptor> {
+        Self::lock(&self.pending).clone()
+    }
+
+    pub fn is_running(&self, job: &Uuid) -> bool {
+        self.jobs.contains_key(job)
+    }
+
+    fn launch(&mut self, descriptor: &TaskDescriptor) -> Result<(), PerformError> {
+        let launcher = self.launchers.get(&descriptor.name).ok_or_else(|| {
+            PerformError::task(RemoteError {
+                message: format!("unknown resumable task: {}", descriptor.name),
+            })
+        })?;
+        let job = launcher(&descriptor.params)?;
+        let (id, pending, journal) = (descriptor.id, self.pending.clone(), self.journal.clone());
+        let job = async move {
+            let result = job.await;
+            let mut pending = Self::lock(&pending);
+            pending.retain(|descriptor| descriptor.id != id);
+            journal.save(&pending);
+            result
+        };
+        let session = <Session<P> as crate::Perform<P::Value>>::try_activate();
+        #[cfg(not(target_arch = "wasm32"))]
+        session.perform_result_spawned(job);
+        #[cfg(target_arch = "wasm32")]
+        session.perform_result_with_spawn_local(job);
+        self.jobs.insert(id, session);
+        Ok(())
+    }
+
+    pub fn perform<T: Serialize>(&mut self, name: &str, params: &T) -> Result<Uuid, PerformError> {
+        let descriptor = TaskDescriptor {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            params: serde_json::to_string(params).map_err(PerformError::task)?,
+        };
+        {
+            let mut pending = Self::lock(&self.pending);
+            pending.push(descriptor.clone());
+            self.journal.save(&pending);
+        }
+        if let Err(error) = self.launch(&descriptor) {
+            let mut pending = Self::lock(&self.pending);
+            pending.retain(|pending| pending.id != descriptor.id);
+            self.journal.save(&pending);
+            return Err(error);
+        }
+        Ok(descriptor.id)
+    }
+
+    pub fn resume(&mut self) -> usize {
+        let waiting = self
+            .pending()
+            .into_iter()
+            .filter(|descriptor| !self.jobs.contains_key(&descriptor.id))
+            .filter(|descriptor| self.launchers.contains_key(&descriptor.name))
+            .collect::<Vec<_>>();
+        waiting
+            .iter()
+            .filter(|descriptor| self.launch(descriptor).is_ok())
+            .count()
+    }
+
+    pub fn try_take(&mut self, job: &Uuid) -> Result<P::Value, PerformError> {
+        let session = self.jobs.get(job).ok_or(PerformError::Empty)?;
+        let taken = <Session<P> as crate::Perform<P::Value>>::try_take(session);
+        if !matches!(taken, Err(PerformError::Empty | PerformError::Locked)) {
+            self.jobs.remove(job);
+        }
+        taken
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::{Journal, MemoryJournal, ResumableRegistry};
+    use crate::PerformError;
+    use std::time::Duration;
+
+    mod jobs {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    async fn unfinished_jobs_resume_after_a_reload() {
+        let journal = MemoryJournal::default();
+        let mut before = ResumableRegistry::<jobs::Provider>::new(journal.clone());
+        before.register("double", |n: u32| async move {
+            futures::future::pending::<()>().await;
+            Ok(n)
+        });
+        let job = before.perform("double", &21_u32).unwrap();
+        assert_eq!(journal.load().len(), 1);
+        assert!(before.perform("missing", &0_u32).is_err());
+        assert_eq!(journal.load().len(), 1);
+        drop(before);
+
+        let mut after = ResumableRegistry::<jobs::Provider>::new(journal.clone());
+        assert_eq!(after.pending()[0].id, job);
+        assert_eq!(after.resume(), 0);
+        after.register("double", |n: u32| async move { Ok(n * 2) });
+        assert_eq!(after.resume(), 1);
+        assert!(after.is_running(&job));
+
+        let mut taken = Err(PerformError::Empty);
+        for _ in 0..100 {
+            taken = after.try_take(&job);
+            if !matches!(taken, Err(PerformError::Empty)) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert_eq!(taken.unwrap(), 42);
+        assert!(journal.load().is_empty());
+        assert!(!after.is_running(&job));
+    }
+}
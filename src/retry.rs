@@ -0,0 +1,133 @@
+use crate::{PerformError, Uuid};
+use std::future::Future;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Backoff {
+    pub initial: Duration,
+    pub multiplier: f64,
+    pub max: Duration,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_millis(200),
+            multiplier: 2.,
+            max: Duration::from_secs(10),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub backoff: Backoff,
+    pub jitter: f64,
+    pub wait_online: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            backoff: Backoff::default(),
+            jitter: 0.2,
+            wait_online: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let base = self.backoff.initial.as_secs_f64() * self.backoff.multiplier.powi(exponent);
+        let base = base.min(self.backoff.max.as_secs_f64());
+        let jitter = self.jitter.clamp(0., 1.) * (2. * unit_random() - 1.);
+        Duration::try_from_secs_f64((base * (1. + jitter)).max(0.)).unwrap_or(self.backoff.max)
+    }
+
+    pub(crate) async fn run<F, Fut, T>(&self, mut factory: F) -> (Result<T, PerformError>, u32)
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, PerformError>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match factory().await {
+                Err(PerformError::Cancelled) => return (Err(PerformError::Cancelled), attempt),
+                Err(error) if attempt < self.max_attempts => {
+                    log::debug!("attempt {} failed: {}", attempt, error);
+                    match self.wait_online && !crate::connectivity::reachable().await {
+                        true => crate::connectivity::online().await,
+                        false => crate::time::sleep(self.delay(attempt)).await,
+                    }
+                    attempt += 1;
+                }
+                result => return (result, attempt),
+            }
+        }
+    }
+}
+
+fn unit_random() -> f64 {
+    let random = Uuid::new_v4().as_u128() as u64 & (u64::MAX >> 2);
+    (random >> 9) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Backoff, RetryPolicy};
+    use std::time::Duration;
+
+    #[test]
+    fn delay_grows_and_caps() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            backoff: Backoff {
+                initial: Duration::from_millis(100),
+                multiplier: 2.,
+                max: Duration::from_millis(500),
+            },
+            jitter: 0.,
+            wait_online: false,
+        };
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(3), Duration::from_millis(400));
+        assert_eq!(policy.delay(8), Duration::from_millis(500));
+
+        let jittered = RetryPolicy {
+            jitter: 0.5,
+            ..policy
+        };
+        for _ in 0..32 {
+            let delay = jittered.delay(1);
+            assert!(delay >= Duration::from_millis(50) && delay <= Duration::from_millis(150));
+        }
+    }
+
+    #[test]
+    fn oversized_delays_fall_back_to_the_cap() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            backoff: Backoff {
+                initial: Duration::MAX,
+                multiplier: 2.,
+                max: Duration::MAX,
+            },
+            jitter: 0.,
+            wait_online: false,
+        };
+        for attempt in 1..8 {
+            assert_eq!(policy.delay(attempt), Duration::MAX);
+        }
+    }
+
+    #[test]
+    fn jitter_spans_the_whole_unit_interval() {
+        let samples = (0..256).map(|_| super::unit_random()).collect::<Vec<_>>();
+        assert!(samples.iter().all(|sample| (0. ..1.).contains(sample)));
+        assert!(samples.iter().any(|sample| *sample < 0.25));
+        assert!(samples.iter().any(|sample| *sample > 0.75));
+    }
+}
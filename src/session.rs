@@ -0,0 +1,1481 @@
+use crate::lifecycle::{self, Lifecycle};
+use crate::store::{lock_and_do_mut, try_lock_and_do_mut, Permit, Slot};
+use crate::time::Instant;
+use crate::{
+    async_trait, ok_or_empty, InsertOptions, Perform, PerformError, Store, StoreProvider, TaskMeta,
+    Uuid,
+};
+use crate::{CancellationToken, PerformHandle, Priority, RetryPolicy};
+use crate::{PollAdapter, PollEvent};
+use futures::future::{self, AbortHandle, Abortable, Either};
+use futures::FutureExt;
+use std::collections::HashMap;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::ops::ControlFlow;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+pub(crate) type Taken<T> = (Result<T, PerformError>, Option<TaskMeta>);
+
+pub struct Session<P> {
+    id: Uuid,
+    ttl: Option<Duration>,
+    priority: Priority,
+    generation: AtomicU64,
+    cancellation: std::sync::Mutex<CancellationToken>,
+    alive: Arc<()>,
+    provider: PhantomData<P>,
+}
+
+impl<P> Session<P> {
+    fn with_id(id: Uuid) -> Self {
+        Self {
+            id,
+            ttl: None,
+            priority: Priority::default(),
+            generation: AtomicU64::new(0),
+            cancellation: Default::default(),
+            alive: Arc::new(()),
+            provider: PhantomData,
+        }
+    }
+
+    pub fn ttl(&self) -> Option<Duration> {
+        self.ttl
+    }
+    pub fn set_ttl(&mut self, ttl: Option<Duration>) {
+        self.ttl = ttl;
+    }
+
+    pub fn priority(&self) -> Priority {
+        self.priority
+    }
+    pub fn set_priority(&mut self, priority: Priority) {
+        self.priority = priority;
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+    pub(crate) fn next_generation(&self) -> InsertOptions {
+        InsertOptions {
+            ttl: self.ttl,
+            generation: self.generation.fetch_add(1, Ordering::SeqCst) + 1,
+            started_at: Some(Instant::now()),
+            ..Default::default()
+        }
+    }
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancellation
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+    pub fn cancel(&self) {
+        let mut token = self
+            .cancellation
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        std::mem::take(&mut *token).cancel();
+    }
+
+    fn current_generation(&self) -> InsertOptions {
+        InsertOptions {
+            ttl: self.ttl,
+            generation: self.generation(),
+            ..Default::default()
+        }
+    }
+}
+
+impl<P: StoreProvider> Session<P> {
+    pub async fn perform_and_take<Fut>(fut: Fut) -> Result<P::Value, PerformError>
+    where
+        Fut: Future<Output = P::Value> + 'static,
+    {
+        let session = Self::activate().await;
+        session.perform_result(fut.map(Ok)).await;
+        lock_and_do_mut(P::shared(), |store| {
+            store.untrack(&session.id);
+            ok_or_empty(store.remove(&session.id))
+        })
+        .await
+    }
+    pub async fn try_take_timeout(&self, timeout: Duration) -> Result<P::Value, PerformError> {
+        self.take_when(timeout, |store| ok_or_empty(store.remove(&self.id)))
+            .await
+    }
+    pub(crate) async fn take_when<R, F>(
+        &self,
+        timeout: Duration,
+        mut take: F,
+    ) -> Result<R, PerformError>
+    where
+        F: FnMut(&mut Store<P::Value>) -> Result<R, PerformError>,
+    {
+        let deadline = Instant::now().checked_add(timeout);
+        loop {
+            let completed = P::shared().completed();
+            match lock_and_do_mut(P::shared(), &mut take).await {
+                Err(PerformError::Empty) => {}
+                result => return result,
+            }
+            match deadline {
+                Some(deadline) if Instant::now() >= deadline => return Err(PerformError::TimedOut),
+                Some(deadline) => crate::time::deadline_at(deadline, completed).await?,
+                None => completed.await,
+            }
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn perform_spawned<Fut>(&self, fut: Fut) -> PerformHandle
+    where
+        Fut: Future<Output = P::Value> + 'static,
+    {
+        self.perform_with_spawn_local(fut)
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn perform_spawned<Fut>(&self, fut: Fut) -> PerformHandle
+    where
+        Fut: Future<Output = P::Value> + 'static + Send,
+    {
+        let (handle, task) =
+            self.abortable_task(fut.map(|value| (Ok(value), 1)), Dispatch::default());
+        spawn_task::<P, _>(task);
+        handle
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn perform_blocking<F>(&self, f: F) -> PerformHandle
+    where
+        F: FnOnce() -> P::Value + Send + 'static,
+    {
+        self.perform_result_spawned(async move {
+            tokio::task::spawn_blocking(f)
+                .await
+                .map_err(PerformError::task)
+        })
+    }
+    /// Without `wasm-threads` there is no thread to block on; offload through
+    /// `perform_blocking_task` and a worker pool instead.
+    #[cfg(all(target_arch = "wasm32", feature = "wasm-threads"))]
+    pub fn perform_blocking<F>(&self, f: F) -> PerformHandle
+    where
+        F: FnOnce() -> P::Value + Send + 'static,
+    {
+        self.perform_threaded(async move { f() })
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn try_perform_spawned<Fut>(&self, fut: Fut) -> Result<PerformHandle, PerformError>
+    where
+        Fut: Future<Output = P::Value> + 'static,
+    {
+        self.try_perform_with_spawn_local(fut)
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn try_perform_spawned<Fut>(&self, fut: Fut) -> Result<PerformHandle, PerformError>
+    where
+        Fut: Future<Output = P::Value> + 'static + Send,
+    {
+        let dispatch = self.try_dispatch()?;
+        let (handle, task) = self.abortable_task(fut.map(|value| (Ok(value), 1)), dispatch);
+        spawn_task::<P, _>(task);
+        Ok(handle)
+    }
+    pub fn try_perform_with_spawn_local<Fut>(&self, fut: Fut) -> Result<PerformHandle, PerformError>
+    where
+        Fut: Future<Output = P::Value> + 'static,
+    {
+        let dispatch = self.try_dispatch()?;
+        let (handle, task) = self.abortable_task(fut.map(|value| (Ok(value), 1)), dispatch);
+        spawn_local_task::<P, _>(task);
+        Ok(handle)
+    }
+
+    #[cfg(feature = "web-sys")]
+    pub fn perform_when_idle<Fut>(&self, fut: Fut, timeout: Duration) -> PerformHandle
+    where
+        Fut: Future<Output = P::Value> + 'static,
+    {
+        self.perform_with_spawn_local(async move {
+            crate::time::idle(timeout).await;
+            fut.await
+        })
+    }
+
+    pub async fn perform_result<Fut>(&self, fut: Fut)
+    where
+        Fut: Future<Output = Result<P::Value, PerformError>> + 'static,
+    {
+        self.perform_attempted(fut.map(|result| (result, 1)), Dispatch::default())
+            .await
+    }
+    #[cfg(target_arch = "wasm32")]
+    pub fn perform_result_spawned<Fut>(&self, fut: Fut) -> PerformHandle
+    where
+        Fut: Future<Output = Result<P::Value, PerformError>> + 'static,
+    {
+        self.perform_result_with_spawn_local(fut)
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn perform_result_spawned<Fut>(&self, fut: Fut) -> PerformHandle
+    where
+        Fut: Future<Output = Result<P::Value, PerformError>> + 'static + Send,
+    {
+        let (handle, task) =
+            self.abortable_task(fut.map(|result| (result, 1)), Dispatch::default());
+        spawn_task::<P, _>(task);
+        handle
+    }
+    pub fn perform_with_spawn_local<Fut>(&self, fut: Fut) -> PerformHandle
+    where
+        Fut: Future<Output = P::Value> + 'static,
+    {
+        self.perform_result_with_spawn_local(fut.map(Ok))
+    }
+    pub fn perform_result_with_spawn_local<Fut>(&self, fut: Fut) -> PerformHandle
+    where
+        Fut: Future<Output = Result<P::Value, PerformError>> + 'static,
+    {
+        let (handle, task) =
+            self.abortable_task(fut.map(|result| (result, 1)), Dispatch::default());
+        spawn_local_task::<P, _>(task);
+        handle
+    }
+
+    pub async fn perform_with_retry<F, Fut>(&self, policy: RetryPolicy, factory: F)
+    where
+        F: FnMut() -> Fut + 'static,
+        Fut: Future<Output = Result<P::Value, PerformError>> + 'static,
+    {
+        self.perform_attempted(
+            async move { policy.run(factory).await },
+            Dispatch::default(),
+        )
+        .await
+    }
+    pub fn perform_with_retry_with_spawn_local<F, Fut>(
+        &self,
+        policy: RetryPolicy,
+        factory: F,
+    ) -> PerformHandle
+    where
+        F: FnMut() -> Fut + 'static,
+        Fut: Future<Output = Result<P::Value, PerformError>> + 'static,
+    {
+        let (handle, task) = self.abortable_task(
+            async move { policy.run(factory).await },
+            Dispatch::default(),
+        );
+        spawn_local_task::<P, _>(task);
+        handle
+    }
+
+    fn try_dispatch(&self) -> Result<Dispatch, PerformError> {
+        let slot = P::shared().try_acquire_slot().ok_or(PerformError::Busy)?;
+        try_lock_and_do_mut(P::shared(), |store| store.try_reserve(&self.id))?;
+        Ok(Dispatch {
+            reserved: true,
+            ..Dispatch::slot(slot)
+        })
+    }
+    pub(crate) async fn perform_attempted<Fut>(&self, fut: Fut, dispatch: Dispatch)
+    where
+        Fut: Future<Output = (Result<P::Value, PerformError>, u32)> + 'static,
+    {
+        self.perform_staged(|_| fut, dispatch).await
+    }
+    pub(crate) async fn perform_staged<F, Fut>(&self, stage: F, dispatch: Dispatch)
+    where
+        F: FnOnce(InsertOptions) -> Fut,
+        Fut: Future<Output = (Result<P::Value, PerformError>, u32)> + 'static,
+    {
+        let (id, mut options) = (self.id, self.next_generation());
+        let dispatch = Dispatch {
+            priority: self.priority,
+            ..dispatch
+        };
+        let fut = stage(options);
+        if !dispatch.reserved {
+            reserve::<P>(id).await;
+        }
+        options.sequence = lock_and_do_mut(P::shared(), |store| store.issue(id)).await;
+        let active = enter::<P>(dispatch.ungated);
+        let outcome = match &active {
+            Ok(_) => gated::<P, _>(id, options.generation, dispatch, fut).await,
+            Err(error) => Some((Err(error.clone()), 0)),
+        };
+        settle::<P>(id, outcome, options).await;
+        drop(active);
+    }
+
+    pub async fn perform_with_timeout<Fut>(&self, fut: Fut, timeout: Duration)
+    where
+        Fut: Future<Output = P::Value> + 'static,
+    {
+        self.perform_attempted(fut.map(|value| (Ok(value), 1)), Dispatch::timeout(timeout))
+            .await
+    }
+    pub fn perform_with_timeout_with_spawn_local<Fut>(
+        &self,
+        fut: Fut,
+        timeout: Duration,
+    ) -> PerformHandle
+    where
+        Fut: Future<Output = P::Value> + 'static,
+    {
+        let (handle, task) =
+            self.abortable_task(fut.map(|value| (Ok(value), 1)), Dispatch::timeout(timeout));
+        spawn_local_task::<P, _>(task);
+        handle
+    }
+
+    pub async fn perform_chunked<F>(&self, step: F, budget: Duration)
+    where
+        F: FnMut() -> ControlFlow<P::Value> + 'static,
+    {
+        self.perform_result(crate::time::chunked(step, budget).map(Ok))
+            .await
+    }
+    pub fn perform_chunked_with_spawn_local<F>(&self, step: F, budget: Duration) -> PerformHandle
+    where
+        F: FnMut() -> ControlFlow<P::Value> + 'static,
+    {
+        self.perform_result_with_spawn_local(crate::time::chunked(step, budget).map(Ok))
+    }
+
+    pub async fn perform_cancellable<F, Fut>(&self, f: F)
+    where
+        F: FnOnce(CancellationToken) -> Fut,
+        Fut: Future<Output = P::Value> + 'static,
+    {
+        let token = self.cancellation_token();
+        self.perform_result(cancellable(f(token.clone()), token))
+            .await
+    }
+    pub fn perform_cancellable_with_spawn_local<F, Fut>(&self, f: F) -> PerformHandle
+    where
+        F: FnOnce(CancellationToken) -> Fut,
+        Fut: Future<Output = P::Value> + 'static,
+    {
+        let token = self.cancellation_token();
+        self.perform_result_with_spawn_local(cancellable(f(token.clone()), token))
+    }
+
+    fn abortable_task<Fut>(
+        &self,
+        fut: Fut,
+        dispatch: Dispatch,
+    ) -> (PerformHandle, impl Future<Output = ()>)
+    where
+        Fut: Future<Output = (Result<P::Value, PerformError>, u32)> + 'static,
+    {
+        self.abortable_stage(|_| fut, dispatch)
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn spawn_staged<F, Fut>(&self, stage: F, dispatch: Dispatch) -> PerformHandle
+    where
+        F: FnOnce(InsertOptions) -> Fut + 'static + Send,
+        Fut: Future<Output = (Result<P::Value, PerformError>, u32)> + 'static + Send,
+    {
+        let (handle, task) = self.abortable_stage(stage, dispatch);
+        spawn_task::<P, _>(task);
+        handle
+    }
+    pub(crate) fn spawn_staged_with_spawn_local<F, Fut>(
+        &self,
+        stage: F,
+        dispatch: Dispatch,
+    ) -> PerformHandle
+    where
+        F: FnOnce(InsertOptions) -> Fut + 'static,
+        Fut: Future<Output = (Result<P::Value, PerformError>, u32)> + 'static,
+    {
+        let (handle, task) = self.abortable_stage(stage, dispatch);
+        spawn_local_task::<P, _>(task);
+        handle
+    }
+    fn abortable_stage<F, Fut>(
+        &self,
+        stage: F,
+        dispatch: Dispatch,
+    ) -> (PerformHandle, impl Future<Output = ()>)
+    where
+        F: FnOnce(InsertOptions) -> Fut + 'static,
+        Fut: Future<Output = (Result<P::Value, PerformError>, u32)> + 'static,
+    {
+        let (id, options) = (self.id, self.next_generation());
+        let dispatch = Dispatch {
+            priority: self.priority,
+            ..dispatch
+        };
+        let alive = Arc::downgrade(&self.alive);
+        let (reserved, ungated) = (dispatch.reserved, dispatch.ungated);
+        let (abort, registration) = AbortHandle::new_pair();
+        let (handle, finish) = PerformHandle::new(abort);
+        let fut = Abortable::new(
+            gated::<P, _>(id, options.generation, dispatch, stage(options)),
+            registration,
+        );
+        let task = async move {
+            let _finish = finish;
+            let mut options = options;
+            if !reserved {
+                reserve::<P>(id).await;
+            }
+            options.sequence = lock_and_do_mut(P::shared(), |store| store.issue(id)).await;
+            let active = enter::<P>(ungated);
+            let outcome = match &active {
+                Ok(_) => match fut.await {
+                    Ok(outcome) if alive.strong_count() > 0 => outcome,
+                    _ => None,
+                },
+                Err(error) => Some((Err(error.clone()), 0)),
+            };
+            settle::<P>(id, outcome, options).await;
+            drop(active);
+        };
+        (handle, task)
+    }
+
+    pub fn try_set_buffer(&self, capacity: Option<usize>) -> Result<(), PerformError> {
+        try_lock_and_do_mut(P::shared(), |store| {
+            store.set_buffer(self.id, capacity);
+            Ok(())
+        })
+    }
+    pub async fn set_buffer(&self, capacity: Option<usize>) {
+        lock_and_do_mut(P::shared(), |store| store.set_buffer(self.id, capacity)).await
+    }
+    pub fn buffered(&self) -> usize {
+        try_lock_and_do_mut(P::shared(), |store| Ok(store.buffered(&self.id))).unwrap_or(0)
+    }
+
+    pub fn attach<A: PollAdapter>(&self, adapter: A) {
+        P::shared()
+            .adapters()
+            .attach(self.id, Arc::downgrade(&self.alive), Arc::new(adapter));
+    }
+    pub fn detach(&self) {
+        P::shared().adapters().detach(&self.id);
+    }
+    pub fn try_on_complete<F>(&self, f: F) -> Result<(), PerformError>
+    where
+        P::Value: Clone,
+        F: FnMut(&Result<P::Value, PerformError>) + Send + 'static,
+    {
+        try_lock_and_do_mut(P::shared(), |store| {
+            store.hook(self.id, Box::new(f));
+            Ok(())
+        })
+    }
+    pub async fn on_complete<F>(&self, f: F)
+    where
+        P::Value: Clone,
+        F: FnMut(&Result<P::Value, PerformError>) + Send + 'static,
+    {
+        lock_and_do_mut(P::shared(), |store| store.hook(self.id, Box::new(f))).await
+    }
+
+    pub fn try_take_with_meta(&self) -> Result<(P::Value, TaskMeta), PerformError> {
+        try_lock_and_do_mut(P::shared(), |store| {
+            with_meta(store.remove_with_meta(&self.id))
+        })
+    }
+    pub async fn take_with_meta(&self) -> Result<(P::Value, TaskMeta), PerformError> {
+        lock_and_do_mut(P::shared(), |store| {
+            with_meta(store.remove_with_meta(&self.id))
+        })
+        .await
+    }
+
+    pub fn try_take_if<F>(&self, predicate: F) -> Result<P::Value, PerformError>
+    where
+        F: FnOnce(&P::Value) -> bool,
+    {
+        try_lock_and_do_mut(P::shared(), |store| {
+            ok_or_empty(store.remove_if(&self.id, predicate))
+        })
+    }
+    pub async fn take_if<F>(&self, predicate: F) -> Result<P::Value, PerformError>
+    where
+        F: FnOnce(&P::Value) -> bool,
+    {
+        lock_and_do_mut(P::shared(), |store| {
+            ok_or_empty(store.remove_if(&self.id, predicate))
+        })
+        .await
+    }
+
+    pub async fn hydrate(&self, value: P::Value) -> Result<(), PerformError> {
+        P::shared().hydrate(self.id, value).await
+    }
+
+    pub fn try_take_latest(&self) -> Result<P::Value, PerformError> {
+        let generation = self.generation();
+        try_lock_and_do_mut(P::shared(), |store| {
+            ok_or_empty(store.remove_latest(&self.id, generation))
+        })
+    }
+    pub(crate) fn try_take_latest_entry(&self) -> Result<Taken<P::Value>, PerformError> {
+        let generation = self.generation();
+        try_lock_and_do_mut(P::shared(), |store| {
+            Ok(store
+                .remove_latest_with_meta(&self.id, generation)
+                .unwrap_or((Err(PerformError::Empty), None)))
+        })
+    }
+    pub async fn take_latest(&self) -> Result<P::Value, PerformError> {
+        let generation = self.generation();
+        lock_and_do_mut(P::shared(), |store| {
+            ok_or_empty(store.remove_latest(&self.id, generation))
+        })
+        .await
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<P: StoreProvider> Perform<P::Value> for Session<P> {
+    fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    fn try_activate() -> Self {
+        crate::inspect::register::<P>();
+        let session = Self::with_id(Uuid::new_v4());
+        let _ = try_lock_and_do_mut(P::shared(), |store| {
+            store.track(session.id, Arc::downgrade(&session.alive));
+            let option = store.insert(session.id, Err(PerformError::Empty))?;
+            ok_or_empty(option)
+        });
+        session
+    }
+    async fn activate() -> Self {
+        crate::inspect::register::<P>();
+        let session = Self::with_id(Uuid::new_v4());
+        let _ = lock_and_do_mut(P::shared(), |store| {
+            store.track(session.id, Arc::downgrade(&session.alive));
+            store.insert(session.id, Err(PerformError::Empty))
+        })
+        .await;
+        session
+    }
+
+    fn perform_with_spawn_local<Fut>(&self, fut: Fut) -> PerformHandle
+    where
+        Fut: Future<Output = P::Value> + 'static,
+    {
+        Session::perform_with_spawn_local(self, fut)
+    }
+    async fn perform<Fut>(&self, fut: Fut)
+    where
+        Fut: Future<Output = P::Value> + 'static + Send,
+    {
+        let (handle, task) =
+            self.abortable_task(fut.map(|value| (Ok(value), 1)), Dispatch::default());
+        let joined = Joined(&handle);
+        crate::spawn_portable(task);
+        joined.0.join().await
+    }
+
+    fn try_ready(&self) -> Result<P::Value, PerformError> {
+        let (id, options) = (self.id, self.current_generation());
+        try_lock_and_do_mut(P::shared(), |store| {
+            let option = store.insert_with(id, Err(PerformError::Empty), options)?;
+            ok_or_empty(option)
+        })
+    }
+
+    fn try_take(&self) -> Result<P::Value, PerformError> {
+        try_lock_and_do_mut(P::shared(), |store| ok_or_empty(store.remove(&self.id)))
+    }
+    async fn take(&self) -> Result<P::Value, PerformError> {
+        lock_and_do_mut(P::shared(), |store| ok_or_empty(store.remove(&self.id))).await
+    }
+
+    fn take_from_id(
+        &self,
+        hash_map: &mut HashMap<Uuid, Result<P::Value, PerformError>>,
+        id: &Uuid,
+    ) -> Result<P::Value, PerformError> {
+        let option = <Self as Perform<P::Value>>::get_as_take(self, hash_map, id);
+        ok_or_empty(option)
+    }
+    fn get_as_take(
+        &self,
+        hash_map: &mut HashMap<Uuid, Result<P::Value, PerformError>>,
+        id: &Uuid,
+    ) -> Option<Result<P::Value, PerformError>> {
+        hash_map.remove_entry(id).map(|(_id, r)| r)
+    }
+}
+
+#[async_trait(?Send)]
+impl<P: StoreProvider> crate::LocalPerform<P::Value> for Session<P> {
+    fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    fn try_activate() -> Self {
+        <Self as Perform<P::Value>>::try_activate()
+    }
+    async fn activate() -> Self {
+        <Self as Perform<P::Value>>::activate().await
+    }
+
+    fn perform_with_spawn_local<Fut>(&self, fut: Fut) -> PerformHandle
+    where
+        Fut: Future<Output = P::Value> + 'static,
+    {
+        Session::perform_with_spawn_local(self, fut)
+    }
+    async fn perform<Fut>(&self, fut: Fut)
+    where
+        Fut: Future<Output = P::Value> + 'static,
+    {
+        self.perform_result(fut.map(Ok)).await
+    }
+
+    fn try_ready(&self) -> Result<P::Value, PerformError> {
+        <Self as Perform<P::Value>>::try_ready(self)
+    }
+    fn try_take(&self) -> Result<P::Value, PerformError> {
+        <Self as Perform<P::Value>>::try_take(self)
+    }
+    async fn take(&self) -> Result<P::Value, PerformError> {
+        <Self as Perform<P::Value>>::take(self).await
+    }
+
+    fn take_from_id(
+        &self,
+        hash_map: &mut HashMap<Uuid, Result<P::Value, PerformError>>,
+        id: &Uuid,
+    ) -> Result<P::Value, PerformError> {
+        <Self as Perform<P::Value>>::take_from_id(self, hash_map, id)
+    }
+    fn get_as_take(
+        &self,
+        hash_map: &mut HashMap<Uuid, Result<P::Value, PerformError>>,
+        id: &Uuid,
+    ) -> Option<Result<P::Value, PerformError>> {
+        <Self as Perform<P::Value>>::get_as_take(self, hash_map, id)
+    }
+}
+
+async fn cancellable<Fut: Future>(
+    fut: Fut,
+    token: CancellationToken,
+) -> Result<Fut::Output, PerformError> {
+    let cancelled = token.cancelled();
+    futures::pin_mut!(fut, cancelled);
+    match future::select(fut, cancelled).await {
+        Either::Left((value, _)) if !token.is_cancelled() => Ok(value),
+        _ => Err(PerformError::Cancelled),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn spawn_task<P: StoreProvider, F: Future<Output = ()> + 'static + Send>(task: F) {
+    match P::shared().is_deferred() {
+        true => crate::spawn::defer_shared(task),
+        false => crate::spawn_portable(task),
+    }
+}
+fn spawn_local_task<P: StoreProvider, F: Future<Output = ()> + 'static>(task: F) {
+    match P::shared().is_deferred() {
+        true => crate::spawn::defer(task),
+        false => crate::spawn_local(task),
+    }
+}
+
+async fn settle<P: StoreProvider>(
+    id: Uuid,
+    outcome: Option<(Result<P::Value, PerformError>, u32)>,
+    mut options: InsertOptions,
+) {
+    let generation = options.generation;
+    let Some((result, attempts)) = outcome else {
+        lifecycle::emit::<P>(id, generation, Lifecycle::Cancelled);
+        release::<P>(id, options.sequence).await;
+        return P::shared().adapters().notify(&id, PollEvent::Complete);
+    };
+    options.attempts = attempts;
+    let settled = lifecycle::is_recorded().then(|| Lifecycle::settled(&result));
+    let _ = P::shared().complete(id, result, options).await;
+    if let Some(lifecycle) = settled {
+        lifecycle::emit::<P>(id, generation, lifecycle);
+    }
+    P::shared().adapters().notify(&id, PollEvent::Complete);
+}
+
+struct Joined<'a>(&'a PerformHandle);
+
+impl Drop for Joined<'_> {
+    fn drop(&mut self) {
+        if !self.0.is_finished() {
+            self.0.abort();
+        }
+    }
+}
+
+pub(crate) fn enter<P: StoreProvider>(
+    ungated: bool,
+) -> Result<Option<Slot<'static>>, PerformError> {
+    match ungated {
+        true => Ok(None),
+        false => P::shared().enter().map(Some).ok_or(PerformError::Closed),
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct Dispatch {
+    priority: Priority,
+    timeout: Option<Duration>,
+    slot: Option<Slot<'static>>,
+    reserved: bool,
+    ungated: bool,
+}
+
+impl Dispatch {
+    fn timeout(timeout: Duration) -> Self {
+        Self {
+            timeout: Some(timeout),
+            ..Default::default()
+        }
+    }
+    fn slot(slot: Slot<'static>) -> Self {
+        Self {
+            slot: Some(slot),
+            ..Default::default()
+        }
+    }
+    pub(crate) fn ungated() -> Self {
+        Self {
+            ungated: true,
+            ..Default::default()
+        }
+    }
+    pub(crate) fn prioritized(priority: Priority) -> Self {
+        Self {
+            priority,
+            ..Default::default()
+        }
+    }
+}
+
+pub(crate) async fn gated<P, Fut>(
+    id: Uuid,
+    generation: u64,
+    dispatch: Dispatch,
+    fut: Fut,
+) -> Option<Fut::Output>
+where
+    P: StoreProvider,
+    Fut: Future<Output = (Result<P::Value, PerformError>, u32)>,
+{
+    if dispatch.ungated {
+        return Some(fut.await);
+    }
+    let admitted = admitted::<P, _>(id, generation, dispatch, fut);
+    P::shared().until_shutdown(admitted).await.flatten()
+}
+
+async fn admitted<P, Fut>(
+    id: Uuid,
+    generation: u64,
+    dispatch: Dispatch,
+    fut: Fut,
+) -> Option<Fut::Output>
+where
+    P: StoreProvider,
+    Fut: Future<Output = (Result<P::Value, PerformError>, u32)>,
+{
+    P::shared().wait_until_online().await;
+    let timeout = loop {
+        let permit = lock_and_do_mut(P::shared(), |store| {
+            store
+                .admit()
+                .map(|()| (store.acquire(id, generation), store.config().timeout))
+        })
+        .await;
+        match permit {
+            Ok((Permit::Granted, default)) => break dispatch.timeout.or(default),
+            Ok((Permit::Wait(delay), _)) => crate::time::sleep(delay).await,
+            Ok((Permit::Coalesced, _)) => return None,
+            Err(error) => return Some((Err(error), 0)),
+        }
+    };
+    let _slot = match dispatch.slot {
+        Some(slot) => slot,
+        None => P::shared().acquire_slot(dispatch.priority).await,
+    };
+    lifecycle::emit::<P>(id, generation, Lifecycle::Started);
+    P::shared().adapters().notify(&id, PollEvent::Pending);
+    #[cfg(all(feature = "perf-marks", not(target_arch = "wasm32")))]
+    let fut = crate::perf::in_span::<P, _>(id, generation, fut);
+    Some(match timeout {
+        Some(timeout) => crate::time::timeout(timeout, fut)
+            .await
+            .unwrap_or_else(|error| (Err(error), 1)),
+        None => fut.await,
+    })
+}
+
+async fn release<P: StoreProvider>(id: Uuid, sequence: Option<u64>) {
+    lock_and_do_mut(P::shared(), |store| {
+        if let Some(sequence) = sequence {
+            store.release(sequence);
+        }
+        store.unreserve(&id);
+    })
+    .await;
+}
+
+async fn reserve<P: StoreProvider>(id: Uuid) {
+    let reserve = |store: &mut Store<P::Value>| (store.space(), store.try_reserve(&id));
+    let (space, Err(_)) = lock_and_do_mut(P::shared(), reserve).await else {
+        return;
+    };
+    loop {
+        let freed = space.notified();
+        match lock_and_do_mut(P::shared(), |store| store.try_reserve(&id)).await {
+            Err(_) => freed.await,
+            Ok(_) => return,
+        }
+    }
+}
+
+fn with_meta<T>(
+    option: Option<(Result<T, PerformError>, Option<TaskMeta>)>,
+) -> Result<(T, TaskMeta), PerformError> {
+    match option {
+        Some((Ok(value), Some(meta))) => Ok((value, meta)),
+        Some((Ok(_), None)) => Err(PerformError::Empty),
+        Some((Err(error), _)) => Err(error),
+        None => Err(PerformError::Empty),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Perform, PerformError};
+    use std::time::Duration;
+
+    mod waited {
+        crate::build_perform!(u32);
+    }
+
+    #[test]
+    fn map_takes_keep_their_original_signature() {
+        use std::collections::HashMap;
+        let session = waited::Session::try_activate();
+        let mut map = HashMap::from([(*session.id(), Ok(7))]);
+        assert_eq!(session.take_from_id(&mut map, session.id()).unwrap(), 7);
+        assert!(session.get_as_take(&mut map, session.id()).is_none());
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn try_take_timeout_waits_for_completion() {
+        let session = waited::Session::activate().await;
+        let timed_out = session.try_take_timeout(Duration::from_millis(10)).await;
+        assert!(matches!(timed_out, Err(PerformError::TimedOut)));
+
+        let (taken, _) = tokio::join!(session.try_take_timeout(Duration::from_secs(5)), async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            session.perform(async { 3 }).await;
+        });
+        assert_eq!(taken.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn unbounded_timeouts_wait_without_overflowing() {
+        let session = waited::Session::activate().await;
+        let (taken, _) = tokio::join!(session.try_take_timeout(Duration::MAX), async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            session.perform(async { 4 }).await;
+        });
+        assert_eq!(taken.unwrap(), 4);
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn take_latest_ignores_superseded_results() {
+        let session = waited::Session::activate().await;
+        tokio::join!(
+            session.perform(async {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                1
+            }),
+            async {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                session.perform(async { 2 }).await
+            }
+        );
+        assert_eq!(session.generation(), 2);
+        assert_eq!(session.take_latest().await.unwrap(), 2);
+        assert!(matches!(
+            session.take_latest().await,
+            Err(PerformError::Empty)
+        ));
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn take_with_meta_reports_timing() {
+        let session = waited::Session::activate().await;
+        session
+            .perform(async {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                4
+            })
+            .await;
+        let (value, meta) = session.take_with_meta().await.unwrap();
+        assert_eq!(value, 4);
+        assert_eq!(meta.attempts, 1);
+        assert!(meta.duration() >= Duration::from_millis(10));
+        assert!(matches!(
+            session.take_with_meta().await,
+            Err(PerformError::Empty)
+        ));
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn on_complete_runs_each_time_a_value_is_stored() {
+        use std::sync::{Arc, Mutex};
+        let session = waited::Session::activate().await;
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorder = seen.clone();
+        session
+            .on_complete(move |result| recorder.lock().unwrap().push(result.clone().ok()))
+            .await;
+        session.perform(async { 7 }).await;
+        session
+            .perform_result(async { Err(PerformError::Cancelled) })
+            .await;
+        assert_eq!(*seen.lock().unwrap(), vec![Some(7), None]);
+    }
+
+    mod hooked {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn on_complete_runs_after_the_store_is_unlocked() {
+        use crate::StoreProvider;
+        use std::sync::{Arc, Mutex};
+        let session = hooked::Session::activate().await;
+        let unlocked = Arc::new(Mutex::new(Vec::new()));
+        let recorder = unlocked.clone();
+        session
+            .on_complete(move |_| {
+                let free = hooked::Provider::store().try_lock().is_ok();
+                recorder.lock().unwrap().push(free);
+            })
+            .await;
+        session.perform(async { 5 }).await;
+        assert_eq!(*unlocked.lock().unwrap(), vec![true]);
+        assert_eq!(session.take().await.unwrap(), 5);
+    }
+
+    mod buffered {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn set_buffer_queues_results_and_applies_backpressure() {
+        let session = buffered::Session::activate().await;
+        session.set_buffer(Some(2)).await;
+        session.perform(async { 1 }).await;
+        session.perform_spawned(async { 2 }).join().await;
+        assert_eq!(session.buffered(), 2);
+        assert!(matches!(
+            session.try_perform_spawned(async { 3 }),
+            Err(PerformError::StoreFull)
+        ));
+
+        let (_, taken) = tokio::join!(session.perform(async { 3 }), async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            session.take().await
+        });
+        assert_eq!(taken.unwrap(), 1);
+        assert_eq!(session.take().await.unwrap(), 2);
+        assert_eq!(session.take().await.unwrap(), 3);
+        assert!(matches!(session.take().await, Err(PerformError::Empty)));
+    }
+
+    mod one_shot {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn perform_and_take_leaves_nothing_behind() {
+        use crate::StoreProvider;
+        let taken = one_shot::Session::perform_and_take(async { 8 }).await;
+        assert_eq!(taken.unwrap(), 8);
+        let store = one_shot::Provider::shared().store().lock().await;
+        assert!(store.is_empty());
+        assert!(store.check().is_clean());
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn perform_blocking_keeps_the_runtime_responsive() {
+        let session = waited::Session::activate().await;
+        session.perform_blocking(|| {
+            std::thread::sleep(Duration::from_millis(30));
+            (1..=10).sum()
+        });
+        let ticked = tokio::time::timeout(Duration::from_millis(20), async {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        })
+        .await;
+        assert!(ticked.is_ok());
+        let taken = session.try_take_timeout(Duration::from_secs(5)).await;
+        assert_eq!(taken.unwrap(), 55);
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn hydrate_seeds_the_latest_value() {
+        let session = waited::Session::activate().await;
+        session.hydrate(11).await.unwrap();
+        assert_eq!(session.take_latest().await.unwrap(), 11);
+
+        session.perform(async { 12 }).await;
+        session.hydrate(13).await.unwrap();
+        assert_eq!(session.take_latest().await.unwrap(), 13);
+    }
+
+    mod local {
+        crate::build_perform!(u32);
+
+        #[tokio::test]
+        #[cfg(not(target_arch = "wasm32"))]
+        async fn local_perform_accepts_non_send_futures() {
+            use crate::LocalPerform;
+            use std::rc::Rc;
+            let session = Session::activate().await;
+            let shared = Rc::new(6);
+            session.perform(async move { *shared }).await;
+            assert_eq!(session.take().await.unwrap(), 6);
+        }
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn perform_spawned_does_not_block_the_caller() {
+        let session = waited::Session::activate().await;
+        session.perform_spawned(async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            5
+        });
+        assert!(matches!(session.take().await, Err(PerformError::Empty)));
+        let taken = session.try_take_timeout(Duration::from_secs(5)).await;
+        assert_eq!(taken.unwrap(), 5);
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn cancel_reaches_the_performed_future() {
+        let session = waited::Session::activate().await;
+        tokio::join!(
+            session.perform_cancellable(|token| async move {
+                token.cancelled().await;
+                6
+            }),
+            async {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                session.cancel();
+            }
+        );
+        assert!(matches!(session.take().await, Err(PerformError::Cancelled)));
+        assert!(!session.cancellation_token().is_cancelled());
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn cancel_interrupts_futures_that_ignore_the_token() {
+        let session = waited::Session::activate().await;
+        let early = session.cancellation_token();
+        session.cancel();
+        early.cancel();
+        assert!(!session.cancellation_token().is_cancelled());
+        tokio::join!(
+            session.perform_cancellable(|_| async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                8
+            }),
+            async {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                session.cancel();
+            }
+        );
+        assert!(matches!(session.take().await, Err(PerformError::Cancelled)));
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn perform_with_timeout_stores_timed_out() {
+        let session = waited::Session::activate().await;
+        let slow = async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            7
+        };
+        session
+            .perform_with_timeout(slow, Duration::from_millis(5))
+            .await;
+        assert!(matches!(session.take().await, Err(PerformError::TimedOut)));
+
+        session
+            .perform_with_timeout(async { 8 }, Duration::from_secs(5))
+            .await;
+        assert_eq!(session.take().await.unwrap(), 8);
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn perform_with_retry_counts_attempts() {
+        let session = waited::Session::activate().await;
+        let policy = crate::RetryPolicy {
+            max_attempts: 5,
+            backoff: crate::Backoff {
+                initial: Duration::from_millis(1),
+                ..Default::default()
+            },
+            jitter: 0.,
+            wait_online: false,
+        };
+        let calls = std::sync::Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let counter = calls.clone();
+        session
+            .perform_with_retry(policy, move || {
+                let call = counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                async move {
+                    match call {
+                        0 | 1 => Err(PerformError::TimedOut),
+                        _ => Ok(9),
+                    }
+                }
+            })
+            .await;
+        let (value, meta) = session.take_with_meta().await.unwrap();
+        assert_eq!((value, meta.attempts), (9, 3));
+
+        let policy = crate::RetryPolicy {
+            max_attempts: 2,
+            ..policy
+        };
+        session
+            .perform_with_retry(policy, || async { Err(PerformError::TimedOut) })
+            .await;
+        assert!(matches!(session.take().await, Err(PerformError::TimedOut)));
+    }
+
+    mod breaker {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn circuit_opens_after_consecutive_failures() {
+        use crate::StoreProvider;
+        breaker::Provider::configure(crate::PerformConfig {
+            circuit_breaker: Some(crate::CircuitBreaker {
+                threshold: 2,
+                cool_down: Duration::from_millis(30),
+            }),
+            ..Default::default()
+        })
+        .await;
+        let session = breaker::Session::activate().await;
+        for _ in 0..2 {
+            session
+                .perform_result(async { Err(PerformError::TimedOut) })
+                .await;
+            assert!(matches!(session.take().await, Err(PerformError::TimedOut)));
+        }
+
+        let ran = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let flag = ran.clone();
+        session
+            .perform(async move {
+                flag.store(true, std::sync::atomic::Ordering::SeqCst);
+                1
+            })
+            .await;
+        assert!(matches!(
+            session.take().await,
+            Err(PerformError::CircuitOpen)
+        ));
+        assert!(!ran.load(std::sync::atomic::Ordering::SeqCst));
+
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        session.perform(async { 2 }).await;
+        assert_eq!(session.take().await.unwrap(), 2);
+        assert!(!breaker::Provider::store().lock().await.is_circuit_open());
+    }
+
+    mod deadline {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn configured_timeout_applies_unless_overridden() {
+        use crate::StoreProvider;
+        deadline::Provider::configure(crate::PerformConfig {
+            timeout: Some(Duration::from_millis(10)),
+            ..Default::default()
+        })
+        .await;
+        let session = deadline::Session::activate().await;
+        session
+            .perform(async {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                1
+            })
+            .await;
+        assert!(matches!(session.take().await, Err(PerformError::TimedOut)));
+
+        session
+            .perform_with_timeout(
+                async {
+                    tokio::time::sleep(Duration::from_millis(30)).await;
+                    2
+                },
+                Duration::from_millis(200),
+            )
+            .await;
+        assert_eq!(session.take().await.unwrap(), 2);
+    }
+
+    mod bounded {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn max_in_flight_queues_or_reports_busy() {
+        use crate::StoreProvider;
+        bounded::Provider::configure(crate::PerformConfig {
+            max_in_flight: Some(1),
+            ..Default::default()
+        })
+        .await;
+        let (first, second) = (
+            bounded::Session::activate().await,
+            bounded::Session::activate().await,
+        );
+        first.perform_spawned(async {
+            tokio::time::sleep(Duration::from_millis(30)).await;
+            1
+        });
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        assert_eq!(bounded::Provider::shared().in_flight(), 1);
+        assert!(matches!(
+            second.try_perform_spawned(async { 2 }),
+            Err(PerformError::Busy)
+        ));
+
+        second.perform(async { 2 }).await;
+        assert_eq!(first.take().await.unwrap(), 1);
+        assert_eq!(second.take().await.unwrap(), 2);
+        assert_eq!(bounded::Provider::shared().in_flight(), 0);
+    }
+
+    mod prioritized {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn queued_performs_dispatch_high_priority_first() {
+        use crate::{Priority, StoreProvider};
+        prioritized::Provider::configure(crate::PerformConfig {
+            max_in_flight: Some(1),
+            ..Default::default()
+        })
+        .await;
+        let blocker = prioritized::Session::activate().await;
+        let mut low = prioritized::Session::activate().await;
+        let mut high = prioritized::Session::activate().await;
+        low.set_priority(Priority::Low);
+        high.set_priority(Priority::High);
+
+        let order = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        blocker.perform_spawned(async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            0
+        });
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        for (session, value) in [(&low, 1), (&high, 2)] {
+            let order = order.clone();
+            session.perform_spawned(async move {
+                order.lock().unwrap().push(value);
+                value
+            });
+            tokio::time::sleep(Duration::from_millis(2)).await;
+        }
+        assert_eq!(prioritized::Provider::shared().queued(), 2);
+        assert_eq!(
+            low.try_take_timeout(Duration::from_secs(5)).await.unwrap(),
+            1
+        );
+        assert_eq!(*order.lock().unwrap(), vec![2, 1]);
+    }
+
+    mod pumped {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn deferred_performs_wait_for_pump() {
+        use crate::StoreProvider;
+        pumped::Provider::configure(crate::PerformConfig {
+            deferred: true,
+            ..Default::default()
+        })
+        .await;
+        let session = pumped::Session::activate().await;
+        session.perform_spawned(async { 3 });
+        session.perform_with_spawn_local(async { 4 });
+        assert!(matches!(session.take().await, Err(PerformError::Empty)));
+        assert_eq!(crate::pump(), 0);
+        assert_eq!(session.take_latest().await.unwrap(), 4);
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn perform_chunked_yields_between_budgets() {
+        let session = waited::Session::activate().await;
+        let mut total = 0;
+        session
+            .perform_chunked(
+                move || {
+                    total += 1;
+                    match total {
+                        1000 => std::ops::ControlFlow::Break(total),
+                        _ => std::ops::ControlFlow::Continue(()),
+                    }
+                },
+                Duration::ZERO,
+            )
+            .await;
+        assert_eq!(session.take().await.unwrap(), 1000);
+    }
+
+    mod drained {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn drain_waits_then_aborts_after_the_deadline() {
+        use crate::{DrainReport, StoreProvider};
+        let (quick, slow) = (
+            drained::Session::activate().await,
+            drained::Session::activate().await,
+        );
+        quick.perform_spawned(async {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            1
+        });
+        slow.perform_spawned(async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            2
+        });
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        let report = drained::Provider::drain(Some(Duration::from_millis(30))).await;
+        assert_eq!(
+            report,
+            DrainReport {
+                completed: 1,
+                aborted: 1
+            }
+        );
+        assert_eq!(quick.try_take().unwrap(), 1);
+        assert!(slow.take().await.is_err());
+
+        quick.perform(async { 3 }).await;
+        assert!(matches!(quick.take().await, Err(PerformError::Closed)));
+
+        drained::Provider::reopen();
+        assert!(!drained::Provider::shared().is_draining());
+        quick.perform(async { 4 }).await;
+        assert_eq!(quick.take().await.unwrap(), 4);
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn perform_handle_observes_and_joins_the_task() {
+        let session = waited::Session::activate().await;
+        let handle = session.perform_spawned(async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            6
+        });
+        assert!(!handle.is_finished());
+        handle.clone().await;
+        assert!(handle.is_finished());
+        assert_eq!(session.take().await.unwrap(), 6);
+
+        let handle = session.perform_spawned(async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            7
+        });
+        handle.abort();
+        handle.join().await;
+        assert!(handle.is_finished() && handle.is_aborted());
+    }
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    #[cfg(target_arch = "wasm32")]
+    async fn perform_with_spawn_local_completes_under_try_take_polling() {
+        let session = waited::Session::activate().await;
+        session.perform_with_spawn_local(async {
+            crate::time::sleep(Duration::from_millis(10)).await;
+            5
+        });
+        assert!(matches!(session.try_take(), Err(PerformError::Empty)));
+        let taken = crate::tests::polled(|| session.try_take()).await;
+        assert_eq!(taken.unwrap(), 5);
+        assert!(session.try_take().is_err());
+    }
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    #[cfg(target_arch = "wasm32")]
+    async fn failures_and_aborts_surface_on_wasm() {
+        let session = waited::Session::activate().await;
+        session.perform_result_with_spawn_local(async { Err(PerformError::Busy) });
+        let failed = crate::tests::polled(|| session.try_take()).await;
+        assert!(matches!(failed, Err(PerformError::Busy)));
+
+        let handle = session.perform_with_spawn_local(async {
+            futures::future::pending::<()>().await;
+            0
+        });
+        handle.abort();
+        handle.join().await;
+        assert!(handle.is_aborted());
+    }
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    #[cfg(all(target_arch = "wasm32", feature = "wasm-threads"))]
+    async fn perform_blocking_returns_through_the_store_on_wasm() {
+        let session = waited::Session::activate().await;
+        session.perform_blocking(|| (1..=4).product());
+        let taken = crate::tests::polled(|| session.try_take()).await;
+        assert_eq!(taken.unwrap(), 24);
+    }
+}
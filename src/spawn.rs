@@ -0,0 +1,211 @@
+#[cfg(not(target_arch = "wasm32"))]
+use futures::future::{BoxFuture, FutureExt};
+use futures::task::{waker, ArcWake};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::{Mutex, OnceLock};
+use std::task::Context;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub trait MaybeSend: Send {}
+#[cfg(not(target_arch = "wasm32"))]
+impl<T: Send> MaybeSend for T {}
+#[cfg(target_arch = "wasm32")]
+pub trait MaybeSend {}
+#[cfg(target_arch = "wasm32")]
+impl<T> MaybeSend for T {}
+
+#[derive(Default)]
+struct Woken(AtomicBool);
+
+impl ArcWake for Woken {
+    fn wake_by_ref(woken: &Arc<Self>) {
+        woken.0.store(true, Ordering::SeqCst);
+    }
+}
+
+struct Deferred<F: ?Sized> {
+    woken: Arc<Woken>,
+    task: Pin<Box<F>>,
+}
+
+impl<F: Future<Output = ()> + ?Sized> Deferred<F> {
+    fn poll_if_woken(&mut self) -> bool {
+        if !self.woken.0.swap(false, Ordering::SeqCst) {
+            return true;
+        }
+        let waker = waker(self.woken.clone());
+        self.task
+            .as_mut()
+            .poll(&mut Context::from_waker(&waker))
+            .is_pending()
+    }
+}
+
+fn deferred<F: ?Sized>(task: Pin<Box<F>>) -> Deferred<F> {
+    Deferred {
+        woken: Arc::new(Woken(AtomicBool::new(true))),
+        task,
+    }
+}
+
+type LocalQueue = VecDeque<Deferred<dyn Future<Output = ()>>>;
+
+thread_local! {
+    static DEFERRED: RefCell<LocalQueue> = RefCell::new(VecDeque::new());
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+type SharedQueue = VecDeque<Deferred<dyn Future<Output = ()> + Send>>;
+
+#[cfg(not(target_arch = "wasm32"))]
+static SHARED: Mutex<SharedQueue> = Mutex::new(VecDeque::new());
+
+#[cfg(not(target_arch = "wasm32"))]
+type Spawner = Box<dyn Fn(BoxFuture<'static, ()>) + Send + Sync>;
+
+#[cfg(not(target_arch = "wasm32"))]
+static SPAWNER: OnceLock<Spawner> = OnceLock::new();
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn set_spawner<S>(spawner: S) -> bool
+where
+    S: Fn(BoxFuture<'static, ()>) + Send + Sync + 'static,
+{
+    SPAWNER.set(Box::new(spawner)).is_ok()
+}
+#[cfg(all(not(target_arch = "wasm32"), feature = "wasm-threads"))]
+pub(crate) fn has_spawner() -> bool {
+    SPAWNER.get().is_some()
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn spawn_portable<F>(fut: F)
+where
+    F: Future<Output = ()> + 'static,
+{
+    crate::spawn_local(fut)
+}
+#[cfg(not(target_arch = "wasm32"))]
+pub fn spawn_portable<F>(fut: F)
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    match SPAWNER.get() {
+        Some(spawner) => spawner(fut.boxed()),
+        None => drop(tokio::spawn(fut)),
+    }
+}
+
+pub(crate) fn defer<F>(fut: F)
+where
+    F: Future<Output = ()> + 'static,
+{
+    DEFERRED.with(|queue| queue.borrow_mut().push_back(deferred(Box::pin(fut))));
+}
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn defer_shared<F>(fut: F)
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    shared().push_back(deferred(Box::pin(fut)));
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn shared() -> std::sync::MutexGuard<'static, SharedQueue> {
+    SHARED
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+pub fn pump() -> usize {
+    let pending = pump_local();
+    #[cfg(not(target_arch = "wasm32"))]
+    let pending = pending + pump_shared();
+    pending
+}
+fn pump_local() -> usize {
+    let mut pending = DEFERRED.with(|queue| std::mem::take(&mut *queue.borrow_mut()));
+    pending.retain_mut(Deferred::poll_if_woken);
+    DEFERRED.with(|queue| {
+        let mut queue = queue.borrow_mut();
+        pending.append(&mut queue);
+        *queue = pending;
+        queue.len()
+    })
+}
+#[cfg(not(target_arch = "wasm32"))]
+fn pump_shared() -> usize {
+    let mut pending = std::mem::take(&mut *shared());
+    pending.retain_mut(Deferred::poll_if_woken);
+    let mut queue = shared();
+    pending.append(&mut queue);
+    *queue = pending;
+    queue.len()
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::{set_spawner, spawn_portable};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    static SPAWNED: AtomicUsize = AtomicUsize::new(0);
+
+    #[tokio::test]
+    async fn custom_spawner_receives_portable_spawns() {
+        assert!(set_spawner(|fut| {
+            SPAWNED.fetch_add(1, Ordering::SeqCst);
+            tokio::spawn(fut);
+        }));
+        let (sender, receiver) = tokio::sync::oneshot::channel();
+        spawn_portable(async move {
+            let _ = sender.send(7);
+        });
+        let received = tokio::time::timeout(Duration::from_secs(1), receiver).await;
+        assert_eq!(received.unwrap().unwrap(), 7);
+        assert!(SPAWNED.load(Ordering::SeqCst) >= 1);
+        assert!(!set_spawner(|_| {}));
+    }
+
+    #[test]
+    fn tasks_deferred_on_other_threads_are_pumped_here() {
+        let (sender, mut receiver) = futures::channel::oneshot::channel();
+        std::thread::spawn(move || {
+            super::defer_shared(async move {
+                let _ = sender.send(9);
+            })
+        })
+        .join()
+        .unwrap();
+        super::pump();
+        assert_eq!(receiver.try_recv().unwrap(), Some(9));
+    }
+
+    #[test]
+    fn pending_tasks_wait_for_their_waker() {
+        use std::sync::{Arc, Mutex};
+        use std::task::Poll;
+        let (polls, parked) = (Arc::new(AtomicUsize::new(0)), Arc::new(Mutex::new(None)));
+        let (counted, stored) = (polls.clone(), parked.clone());
+        super::defer(futures::future::poll_fn(move |context| {
+            counted.fetch_add(1, Ordering::SeqCst);
+            match stored.lock().unwrap().replace(context.waker().clone()) {
+                Some(_) => Poll::Ready(()),
+                None => Poll::Pending,
+            }
+        }));
+        assert_eq!(super::pump_local(), 1);
+        assert_eq!(super::pump_local(), 1);
+        assert_eq!(polls.load(Ordering::SeqCst), 1);
+
+        parked.lock().unwrap().as_ref().unwrap().wake_by_ref();
+        assert_eq!(super::pump_local(), 0);
+        assert_eq!(polls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,117 @@
+use crate::Priority;
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use tokio::sync::Notify;
+
+type QueueKey = (Reverse<Priority>, u64);
+
+pub(super) struct Admission {
+    in_flight: AtomicUsize,
+    max_in_flight: AtomicUsize,
+    freed: Notify,
+    queue: std::sync::Mutex<BTreeSet<QueueKey>>,
+    tickets: AtomicU64,
+}
+
+impl Admission {
+    pub(super) fn new(max_in_flight: Option<usize>) -> Self {
+        Self {
+            in_flight: AtomicUsize::new(0),
+            max_in_flight: AtomicUsize::new(max_in_flight.unwrap_or(usize::MAX)),
+            freed: Notify::new(),
+            queue: Default::default(),
+            tickets: AtomicU64::new(0),
+        }
+    }
+
+    pub(super) fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+    pub(super) fn queued(&self) -> usize {
+        self.queue().len()
+    }
+    pub(super) fn set_limit(&self, max_in_flight: Option<usize>) {
+        self.max_in_flight
+            .store(max_in_flight.unwrap_or(usize::MAX), Ordering::SeqCst);
+        self.freed.notify_waiters();
+    }
+    pub(super) fn try_acquire(&self) -> Option<Slot<'_>> {
+        match self.queue().is_empty() {
+            true => self.reserve(),
+            false => None,
+        }
+    }
+    pub(super) async fn acquire(&self, priority: Priority) -> Slot<'_> {
+        if let Some(slot) = self.try_acquire() {
+            return slot;
+        }
+        let key = (
+            Reverse(priority),
+            self.tickets.fetch_add(1, Ordering::SeqCst),
+        );
+        self.queue().insert(key);
+        let _queued = Queued {
+            queue: &self.queue,
+            freed: &self.freed,
+            key,
+        };
+        loop {
+            let freed = self.freed.notified();
+            if self.queue().first() == Some(&key) {
+                if let Some(slot) = self.reserve() {
+                    return slot;
+                }
+            }
+            freed.await;
+        }
+    }
+    fn reserve(&self) -> Option<Slot<'_>> {
+        let max_in_flight = self.max_in_flight.load(Ordering::SeqCst);
+        self.in_flight
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |in_flight| {
+                (in_flight < max_in_flight).then_some(in_flight + 1)
+            })
+            .ok()
+            .map(|_| Slot::new(&self.in_flight, &self.freed))
+    }
+    fn queue(&self) -> std::sync::MutexGuard<'_, BTreeSet<QueueKey>> {
+        self.queue
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+pub(crate) struct Slot<'a> {
+    in_flight: &'a AtomicUsize,
+    freed: &'a Notify,
+}
+
+impl<'a> Slot<'a> {
+    pub(super) fn new(in_flight: &'a AtomicUsize, freed: &'a Notify) -> Self {
+        Self { in_flight, freed }
+    }
+}
+
+impl Drop for Slot<'_> {
+    fn drop(&mut self) {
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+        self.freed.notify_waiters();
+    }
+}
+
+struct Queued<'a> {
+    queue: &'a std::sync::Mutex<BTreeSet<QueueKey>>,
+    freed: &'a Notify,
+    key: QueueKey,
+}
+
+impl Drop for Queued<'_> {
+    fn drop(&mut self) {
+        self.queue
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(&self.key);
+        self.freed.notify_waiters();
+    }
+}
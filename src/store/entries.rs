@@ -0,0 +1,1069 @@
+use super::Completed;
+use crate::inspect::EntrySnapshot;
+use crate::time::Instant;
+use crate::{
+    CacheStatus, CompletionOrder, EvictionPolicy, PerformConfig, PerformError, RateLimitOverflow,
+    TaskMeta, Uuid,
+};
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+const TOMBSTONE_LIMIT: usize = 1024;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HealReport {
+    pub orphaned_entries: usize,
+    pub stale_owners: usize,
+    pub released_tickets: usize,
+    pub dropped_tombstones: usize,
+}
+
+impl HealReport {
+    pub fn is_clean(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InsertOptions {
+    pub ttl: Option<Duration>,
+    pub generation: u64,
+    pub started_at: Option<Instant>,
+    pub attempts: u32,
+    pub sequence: Option<u64>,
+}
+
+pub(crate) enum Permit {
+    Granted,
+    Wait(Duration),
+    Coalesced,
+}
+
+pub(crate) type Hook<T> = Box<dyn FnMut(&Result<T, PerformError>) + Send>;
+type SharedHook<T> = Arc<std::sync::Mutex<Hook<T>>>;
+type Firing<T> = (Vec<SharedHook<T>>, Result<T, PerformError>);
+
+pub(crate) struct Fired<T>(Vec<Firing<T>>);
+
+impl<T> Fired<T> {
+    pub(crate) fn run(self) {
+        for (hooks, result) in self.0 {
+            for hook in hooks {
+                let mut hook = hook.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                hook(&result);
+            }
+        }
+    }
+}
+
+struct Entry<T> {
+    result: Result<T, PerformError>,
+    meta: Option<TaskMeta>,
+    generation: u64,
+    sequence: Option<u64>,
+    touched: Cell<u64>,
+    completed: Option<u64>,
+    expires_at: Option<Instant>,
+}
+
+impl<T> Entry<T> {
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
+
+pub struct Store<T> {
+    entries: HashMap<Uuid, Entry<T>>,
+    tombstones: VecDeque<(Uuid, PerformError)>,
+    owners: HashMap<Uuid, Weak<()>>,
+    issued: VecDeque<(u64, Uuid)>,
+    config: PerformConfig,
+    tick: u64,
+    clock: Cell<u64>,
+    sequence: u64,
+    failures: u32,
+    open_until: Option<Instant>,
+    tokens: f64,
+    refilled_at: Option<Instant>,
+    waiting: HashMap<Uuid, u64>,
+    hooks: HashMap<Uuid, Vec<SharedHook<T>>>,
+    fired: Vec<Firing<T>>,
+    cloner: Option<fn(&T) -> T>,
+    buffers: HashMap<Uuid, Buffer<T>>,
+    cache_notes: HashMap<Uuid, CacheStatus>,
+    space: Arc<Notify>,
+}
+
+struct Buffer<T> {
+    items: VecDeque<(Result<T, PerformError>, Option<TaskMeta>)>,
+    reserved: usize,
+    capacity: usize,
+}
+
+impl<T> Default for Store<T> {
+    fn default() -> Self {
+        Self::new(PerformConfig::default())
+    }
+}
+
+impl<T> Store<T> {
+    pub fn new(config: PerformConfig) -> Self {
+        Self {
+            entries: HashMap::new(),
+            tombstones: VecDeque::new(),
+            owners: HashMap::new(),
+            issued: VecDeque::new(),
+            config,
+            tick: 0,
+            clock: Cell::new(0),
+            sequence: 0,
+            failures: 0,
+            open_until: None,
+            tokens: 0.,
+            refilled_at: None,
+            waiting: HashMap::new(),
+            hooks: HashMap::new(),
+            fired: Vec::new(),
+            cloner: None,
+            buffers: HashMap::new(),
+            cache_notes: HashMap::new(),
+            space: Arc::new(Notify::new()),
+        }
+    }
+
+    pub fn config(&self) -> &PerformConfig {
+        &self.config
+    }
+    pub fn set_config(&mut self, config: PerformConfig) {
+        self.config = config;
+    }
+
+    pub fn is_circuit_open(&self) -> bool {
+        match self.open_until {
+            Some(open_until) => open_until > Instant::now(),
+            None => self
+                .config
+                .circuit_breaker
+                .is_some_and(|breaker| self.failures >= breaker.threshold),
+        }
+    }
+    pub fn reset_circuit(&mut self) {
+        self.failures = 0;
+        self.open_until = None;
+    }
+    pub(crate) fn admit(&self) -> Result<(), PerformError> {
+        match self.is_circuit_open() {
+            true => Err(PerformError::CircuitOpen),
+            false => Ok(()),
+        }
+    }
+    pub(crate) fn acquire(&mut self, id: Uuid, generation: u64) -> Permit {
+        let Some(limit) = self.config.rate_limit else {
+            return Permit::Granted;
+        };
+        let coalesce = limit.overflow == RateLimitOverflow::Coalesce;
+        if coalesce
+            && self
+                .waiting
+                .get(&id)
+                .is_some_and(|latest| *latest > generation)
+        {
+            return Permit::Coalesced;
+        }
+        let now = Instant::now();
+        let permits = f64::from(limit.permits.max(1));
+        let rate = permits / limit.per.as_secs_f64().max(f64::EPSILON);
+        self.tokens = match self.refilled_at {
+            Some(refilled_at) => {
+                let elapsed = now.saturating_duration_since(refilled_at).as_secs_f64();
+                (self.tokens + elapsed * rate).min(permits)
+            }
+            None => permits,
+        };
+        self.refilled_at = Some(now);
+        if self.tokens >= 1. {
+            self.tokens -= 1.;
+            if let Some(latest) = self.waiting.get_mut(&id) {
+                *latest = (*latest).max(generation);
+            }
+            return Permit::Granted;
+        }
+        if coalesce {
+            self.waiting.insert(id, generation);
+        }
+        Permit::Wait(Duration::from_secs_f64((1. - self.tokens) / rate))
+    }
+
+    pub(super) fn record_outcome(&mut self, result: &Result<T, PerformError>) {
+        let Some(breaker) = self.config.circuit_breaker else {
+            return;
+        };
+        match result {
+            Ok(_) => self.reset_circuit(),
+            Err(PerformError::Cancelled | PerformError::Superseded | PerformError::CircuitOpen) => {
+            }
+            Err(_) => {
+                self.failures = self.failures.saturating_add(1);
+                if self.failures >= breaker.threshold {
+                    self.open_until = Instant::now().checked_add(breaker.cool_down);
+                }
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    pub fn contains(&self, id: &Uuid) -> bool {
+        self.entries.contains_key(id)
+    }
+
+    pub fn insert(
+        &mut self,
+        id: Uuid,
+        result: Result<T, PerformError>,
+    ) -> Result<Option<Result<T, PerformError>>, PerformError> {
+        self.insert_with_ttl(id, result, None)
+    }
+    pub fn insert_with_ttl(
+        &mut self,
+        id: Uuid,
+        result: Result<T, PerformError>,
+        ttl: Option<Duration>,
+    ) -> Result<Option<Result<T, PerformError>>, PerformError> {
+        let options = InsertOptions {
+            ttl,
+            ..Default::default()
+        };
+        self.insert_with(id, result, options)
+    }
+    pub fn insert_with(
+        &mut self,
+        id: Uuid,
+        result: Result<T, PerformError>,
+        options: InsertOptions,
+    ) -> Result<Option<Result<T, PerformError>>, PerformError> {
+        let pending = matches!(result, Err(PerformError::Empty));
+        if let Some(sequence) = options.sequence {
+            self.release(sequence);
+        }
+        if self.buffers.contains_key(&id) {
+            if !pending {
+                self.push_buffered(id, result, options);
+            }
+            return Ok(None);
+        }
+        let sequence = match pending {
+            true => None,
+            false => options.sequence,
+        };
+        match self.entries.get(&id) {
+            Some(existing) if !pending && existing.generation > options.generation => {
+                return Err(PerformError::Superseded);
+            }
+            Some(_) => {}
+            None => {
+                self.make_room(id)?;
+                self.tombstones.retain(|(buried, _)| *buried != id);
+            }
+        }
+        if !pending {
+            self.fire(&id, &result);
+        }
+        self.tick += 1;
+        let now = Instant::now();
+        let (completed, expires_at, meta) = match pending {
+            true => (None, None, None),
+            false => (
+                Some(self.tick),
+                options
+                    .ttl
+                    .or(self.config.ttl)
+                    .and_then(|ttl| now.checked_add(ttl)),
+                Some(TaskMeta {
+                    started_at: options.started_at.unwrap_or(now),
+                    completed_at: now,
+                    attempts: options.attempts.max(1),
+                    cache: self.cache_notes.remove(&id),
+                }),
+            ),
+        };
+        let entry = Entry {
+            result,
+            meta,
+            generation: options.generation,
+            sequence,
+            touched: Cell::new(self.now()),
+            completed,
+            expires_at,
+        };
+        let previous = self.entries.insert(id, entry).map(|entry| entry.result);
+        Ok(previous)
+    }
+    pub fn note_cache(&mut self, id: Uuid, status: CacheStatus) {
+        self.cache_notes.insert(id, status);
+    }
+    pub fn hydrate(&mut self, id: Uuid, value: T) -> Result<(), PerformError> {
+        let options = InsertOptions {
+            generation: self.entries.get(&id).map_or(0, |entry| entry.generation),
+            ..Default::default()
+        };
+        self.insert_with(id, Ok(value), options).map(drop)
+    }
+    fn push_buffered(&mut self, id: Uuid, result: Result<T, PerformError>, options: InsertOptions) {
+        let now = Instant::now();
+        let meta = TaskMeta {
+            started_at: options.started_at.unwrap_or(now),
+            completed_at: now,
+            attempts: options.attempts.max(1),
+            cache: self.cache_notes.remove(&id),
+        };
+        self.fire(&id, &result);
+        if let Some(buffer) = self.buffers.get_mut(&id) {
+            buffer.reserved = buffer.reserved.saturating_sub(1);
+            buffer.items.push_back((result, Some(meta)));
+        }
+    }
+    pub fn set_buffer(&mut self, id: Uuid, capacity: Option<usize>) {
+        match capacity {
+            Some(capacity) => {
+                let buffer = self.buffers.entry(id).or_insert_with(|| Buffer {
+                    items: VecDeque::new(),
+                    reserved: 0,
+                    capacity,
+                });
+                buffer.capacity = capacity;
+            }
+            None => {
+                self.buffers.remove(&id);
+            }
+        }
+        self.space.notify_waiters();
+    }
+    pub fn buffered(&self, id: &Uuid) -> usize {
+        self.buffers.get(id).map_or(0, |buffer| buffer.items.len())
+    }
+    pub(crate) fn try_reserve(&mut self, id: &Uuid) -> Result<bool, PerformError> {
+        match self.buffers.get_mut(id) {
+            Some(buffer) if buffer.items.len() + buffer.reserved >= buffer.capacity => {
+                Err(PerformError::StoreFull)
+            }
+            Some(buffer) => {
+                buffer.reserved += 1;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+    pub(crate) fn unreserve(&mut self, id: &Uuid) {
+        if let Some(buffer) = self.buffers.get_mut(id) {
+            buffer.reserved = buffer.reserved.saturating_sub(1);
+            self.space.notify_waiters();
+        }
+    }
+    pub(crate) fn space(&self) -> Arc<Notify> {
+        self.space.clone()
+    }
+    pub(crate) fn hook(&mut self, id: Uuid, hook: Hook<T>)
+    where
+        T: Clone,
+    {
+        self.cloner.get_or_insert(T::clone);
+        let hook = Arc::new(std::sync::Mutex::new(hook));
+        self.hooks.entry(id).or_default().push(hook);
+    }
+    fn fire(&mut self, id: &Uuid, result: &Result<T, PerformError>) {
+        if let (Some(hooks), Some(clone)) = (self.hooks.get(id), self.cloner) {
+            let result = result.as_ref().map(clone).map_err(Clone::clone);
+            self.fired.push((hooks.clone(), result));
+        }
+    }
+    pub(crate) fn take_fired(&mut self) -> Fired<T> {
+        Fired(std::mem::take(&mut self.fired))
+    }
+
+    pub fn meta(&self, id: &Uuid) -> Option<&TaskMeta> {
+        self.entries.get(id).and_then(|entry| entry.meta.as_ref())
+    }
+
+    pub fn remove(&mut self, id: &Uuid) -> Option<Result<T, PerformError>> {
+        self.remove_with_meta(id).map(|(result, _)| result)
+    }
+    pub fn peek_error(&self, id: &Uuid) -> Option<PerformError> {
+        match self.entries.get(id) {
+            Some(entry) if entry.completed.is_none() => None,
+            Some(entry) => entry.result.as_ref().err().cloned(),
+            None => self
+                .tombstones
+                .iter()
+                .find(|(buried, _)| buried == id)
+                .map(|(_, error)| error.clone()),
+        }
+    }
+    pub(crate) fn inspect(&self) -> (Vec<EntrySnapshot>, Option<PerformError>) {
+        let entries = self
+            .entries
+            .iter()
+            .map(|(id, entry)| EntrySnapshot {
+                id: *id,
+                ready: entry.completed.is_some(),
+                age: entry.meta.map(|meta| meta.age()),
+                error: match &entry.result {
+                    Err(PerformError::Empty) | Ok(_) => None,
+                    Err(error) => Some(error.clone()),
+                },
+            })
+            .collect();
+        let last_error = self
+            .entries
+            .values()
+            .filter(|entry| entry.completed.is_some())
+            .filter_map(|entry| Some((entry.completed, entry.result.as_ref().err()?)))
+            .max_by_key(|(completed, _)| *completed)
+            .map(|(_, error)| error.clone())
+            .or_else(|| self.tombstones.back().map(|(_, error)| error.clone()));
+        (entries, last_error)
+    }
+    pub fn version(&self, id: &Uuid) -> Option<u64> {
+        self.entries.get(id).and_then(|entry| entry.completed)
+    }
+    pub fn is_ready(&self, id: &Uuid) -> bool {
+        if let Some(buffer) = self.buffers.get(id) {
+            return !buffer.items.is_empty();
+        }
+        match self.entries.get(id) {
+            Some(entry) => {
+                self.touch(entry);
+                entry.completed.is_some() && !self.is_held(entry)
+            }
+            None => self.tombstones.iter().any(|(buried, _)| buried == id),
+        }
+    }
+
+    pub fn remove_with_meta(
+        &mut self,
+        id: &Uuid,
+    ) -> Option<(Result<T, PerformError>, Option<TaskMeta>)> {
+        if let Some(buffer) = self.buffers.get_mut(id) {
+            let item = buffer.items.pop_front();
+            self.space.notify_waiters();
+            return item;
+        }
+        if self
+            .entries
+            .get(id)
+            .is_some_and(|entry| self.is_held(entry))
+        {
+            return None;
+        }
+        if let Some(entry) = self.entries.remove(id) {
+            if entry.is_expired(Instant::now()) {
+                return Some((Err(PerformError::Expired), entry.meta));
+            }
+            return Some((entry.result, entry.meta));
+        }
+        let position = self
+            .tombstones
+            .iter()
+            .position(|(buried, _)| buried == id)?;
+        self.tombstones
+            .remove(position)
+            .map(|(_, error)| (Err(error), None))
+    }
+
+    pub fn take_completed(&mut self) -> Completed<T> {
+        let ready: Vec<Uuid> = self
+            .entries
+            .iter()
+            .filter(|(_, entry)| entry.completed.is_some() && !self.is_held(entry))
+            .map(|(id, _)| *id)
+            .collect();
+        let mut completed: Vec<_> = ready
+            .into_iter()
+            .filter_map(|id| self.remove(&id).map(|result| (id, result)))
+            .collect();
+        completed.extend(
+            self.tombstones
+                .drain(..)
+                .map(|(id, error)| (id, Err(error))),
+        );
+        completed
+    }
+
+    pub fn remove_if<F>(&mut self, id: &Uuid, predicate: F) -> Option<Result<T, PerformError>>
+    where
+        F: FnOnce(&T) -> bool,
+    {
+        let entry = self.entries.get(id)?;
+        let keep = match &entry.result {
+            _ if entry.is_expired(Instant::now()) => false,
+            Ok(value) => !predicate(value),
+            Err(PerformError::Empty) => true,
+            Err(_) => false,
+        };
+        if keep {
+            return None;
+        }
+        self.remove(id)
+    }
+
+    pub fn remove_latest(&mut self, id: &Uuid, generation: u64) -> Option<Result<T, PerformError>> {
+        self.remove_latest_with_meta(id, generation)
+            .map(|(result, _)| result)
+    }
+    pub fn remove_latest_with_meta(
+        &mut self,
+        id: &Uuid,
+        generation: u64,
+    ) -> Option<(Result<T, PerformError>, Option<TaskMeta>)> {
+        match self.entries.get(id) {
+            Some(entry) if entry.generation < generation => None,
+            _ => self.remove_with_meta(id),
+        }
+    }
+
+    pub fn purge_expired(&mut self) -> usize {
+        let now = Instant::now();
+        let expired: Vec<Uuid> = self
+            .entries
+            .iter()
+            .filter(|(_, entry)| entry.is_expired(now))
+            .map(|(id, _)| *id)
+            .collect();
+        for id in &expired {
+            self.entries.remove(id);
+            self.bury(*id, PerformError::Expired);
+        }
+        expired.len()
+    }
+
+    pub fn issue(&mut self, id: Uuid) -> Option<u64> {
+        if self.config.completion_order != CompletionOrder::Issued {
+            return None;
+        }
+        self.sequence += 1;
+        self.issued.push_back((self.sequence, id));
+        Some(self.sequence)
+    }
+    pub fn release(&mut self, sequence: u64) {
+        self.issued.retain(|(issued, _)| *issued != sequence);
+    }
+    fn now(&self) -> u64 {
+        self.clock.set(self.clock.get() + 1);
+        self.clock.get()
+    }
+    fn touch(&self, entry: &Entry<T>) {
+        entry.touched.set(self.now());
+    }
+    fn is_held(&self, entry: &Entry<T>) -> bool {
+        match (entry.sequence, self.issued.front()) {
+            (Some(sequence), Some((head, _))) => *head < sequence,
+            _ => false,
+        }
+    }
+
+    pub fn track(&mut self, id: Uuid, owner: Weak<()>) {
+        self.owners.insert(id, owner);
+    }
+    pub fn untrack(&mut self, id: &Uuid) {
+        self.owners.remove(id);
+        self.waiting.remove(id);
+        self.hooks.remove(id);
+        self.buffers.remove(id);
+        self.cache_notes.remove(id);
+    }
+
+    pub fn sweep(&mut self) -> usize {
+        let orphaned: Vec<Uuid> = self
+            .owners
+            .iter()
+            .filter(|(_, owner)| owner.strong_count() == 0)
+            .map(|(id, _)| *id)
+            .collect();
+        let mut swept = 0;
+        self.issued.retain(|(_, id)| !orphaned.contains(id));
+        for id in &orphaned {
+            self.owners.remove(id);
+            self.waiting.remove(id);
+            self.hooks.remove(id);
+            self.buffers.remove(id);
+            if self.entries.remove(id).is_some() {
+                swept += 1;
+            }
+        }
+        swept + self.purge_expired()
+    }
+
+    pub fn check(&self) -> HealReport {
+        let is_live = |id: &Uuid| {
+            self.owners
+                .get(id)
+                .is_some_and(|owner| owner.strong_count() > 0)
+        };
+        HealReport {
+            orphaned_entries: self.entries.keys().filter(|id| !is_live(id)).count(),
+            stale_owners: self.owners.keys().filter(|id| !is_live(id)).count(),
+            released_tickets: self.issued.iter().filter(|(_, id)| !is_live(id)).count(),
+            dropped_tombstones: self
+                .tombstones
+                .iter()
+                .filter(|(id, _)| !is_live(id) || self.entries.contains_key(id))
+                .count(),
+        }
+    }
+    pub fn heal(&mut self) -> HealReport {
+        let report = self.check();
+        if report.is_clean() {
+            return report;
+        }
+        let live: HashSet<Uuid> = self
+            .owners
+            .iter()
+            .filter(|(_, owner)| owner.strong_count() > 0)
+            .map(|(id, _)| *id)
+            .collect();
+        self.entries.retain(|id, _| live.contains(id));
+        self.owners.retain(|id, _| live.contains(id));
+        self.issued.retain(|(_, id)| live.contains(id));
+        let entries = &self.entries;
+        self.tombstones
+            .retain(|(id, _)| live.contains(id) && !entries.contains_key(id));
+        log::warn!("healed an inconsistent store: {:?}", report);
+        report
+    }
+
+    fn make_room(&mut self, id: Uuid) -> Result<(), PerformError> {
+        let capacity = match self.config.capacity {
+            Some(capacity) => capacity,
+            None => return Ok(()),
+        };
+        if self.entries.len() >= capacity {
+            self.purge_expired();
+        }
+        while self.entries.len() >= capacity {
+            let victim = match self.config.eviction {
+                EvictionPolicy::RejectNew => None,
+                EvictionPolicy::LeastRecentlyUsed => self
+                    .entries
+                    .iter()
+                    .filter(|(_, entry)| entry.completed.is_some() && !self.is_held(entry))
+                    .min_by_key(|(_, entry)| entry.touched.get())
+                    .map(|(id, _)| *id),
+                EvictionPolicy::OldestCompleted => self
+                    .entries
+                    .iter()
+                    .filter(|(_, entry)| !self.is_held(entry))
+                    .filter_map(|(id, entry)| entry.completed.map(|completed| (completed, *id)))
+                    .min()
+                    .map(|(_, id)| id),
+            };
+            match victim {
+                Some(victim) => {
+                    log::debug!("evicting {} from a full store", victim);
+                    self.entries.remove(&victim);
+                    self.bury(victim, PerformError::StoreFull);
+                }
+                None => {
+                    self.bury(id, PerformError::StoreFull);
+                    return Err(PerformError::StoreFull);
+                }
+            }
+        }
+        Ok(())
+    }
+    fn bury(&mut self, id: Uuid, error: PerformError) {
+        let limit = self.config.capacity.unwrap_or(TOMBSTONE_LIMIT).max(1);
+        self.tombstones.push_back((id, error));
+        while self.tombstones.len() > limit {
+            self.tombstones.pop_front();
+        }
+    }
+}
+
+impl<T: Clone> Store<T> {
+    pub fn peek(&self, id: &Uuid) -> Option<Result<T, PerformError>> {
+        match self.entries.get(id) {
+            Some(entry) if entry.completed.is_none() || self.is_held(entry) => None,
+            Some(entry) if entry.is_expired(Instant::now()) => Some(Err(PerformError::Expired)),
+            Some(entry) => {
+                self.touch(entry);
+                Some(entry.result.clone())
+            }
+            None => self
+                .tombstones
+                .iter()
+                .find(|(buried, _)| buried == id)
+                .map(|(_, error)| Err(error.clone())),
+        }
+    }
+}
+
+impl<U> Store<Vec<U>> {
+    pub fn append(
+        &mut self,
+        id: Uuid,
+        item: U,
+        options: InsertOptions,
+    ) -> Result<(), PerformError> {
+        match self.entries.get_mut(&id) {
+            Some(Entry {
+                result: Ok(items),
+                generation,
+                ..
+            }) if *generation == options.generation => {
+                items.push(item);
+                Ok(())
+            }
+            _ => {
+                let options = InsertOptions {
+                    sequence: None,
+                    ..options
+                };
+                self.insert_with(id, Ok(vec![item]), options).map(drop)
+            }
+        }
+    }
+    pub(crate) fn take_appended(&mut self, id: &Uuid) -> Vec<U> {
+        match self.entries.get(id) {
+            Some(Entry { result: Ok(_), .. }) => match self.entries.remove(id) {
+                Some(Entry {
+                    result: Ok(items), ..
+                }) => items,
+                _ => Vec::new(),
+            },
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Permit, Store};
+    use crate::{
+        CacheStatus, CircuitBreaker, EvictionPolicy, PerformConfig, PerformError, RateLimit,
+        RateLimitOverflow, Uuid,
+    };
+    use std::time::Duration;
+
+    fn store_of(capacity: usize, eviction: EvictionPolicy) -> Store<u32> {
+        Store::new(PerformConfig {
+            capacity: Some(capacity),
+            eviction,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn reject_new_reports_store_full() {
+        let mut store = store_of(1, EvictionPolicy::RejectNew);
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+        assert!(store.insert(first, Ok(1)).is_ok());
+        assert!(matches!(
+            store.insert(second, Ok(2)),
+            Err(PerformError::StoreFull)
+        ));
+        assert!(matches!(
+            store.remove(&second),
+            Some(Err(PerformError::StoreFull))
+        ));
+        assert!(store.remove(&second).is_none());
+        assert!(matches!(store.remove(&first), Some(Ok(1))));
+    }
+
+    #[test]
+    fn cache_notes_land_in_the_next_meta() {
+        let mut store = Store::<u32>::default();
+        let id = Uuid::new_v4();
+        store.note_cache(id, CacheStatus::Hit);
+        store.insert(id, Err(PerformError::Empty)).unwrap();
+        store.insert(id, Ok(1)).unwrap();
+        assert_eq!(store.meta(&id).unwrap().cache, Some(CacheStatus::Hit));
+        store.insert(id, Ok(2)).unwrap();
+        assert_eq!(store.meta(&id).unwrap().cache, None);
+    }
+
+    #[test]
+    fn least_recently_used_is_evicted() {
+        let mut store = store_of(2, EvictionPolicy::LeastRecentlyUsed);
+        let ids = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+        store.insert(ids[0], Ok(0)).unwrap();
+        store.insert(ids[1], Ok(1)).unwrap();
+        store.insert(ids[0], Ok(10)).unwrap();
+        store.insert(ids[2], Ok(2)).unwrap();
+        assert_eq!(store.len(), 2);
+        assert!(matches!(
+            store.remove(&ids[1]),
+            Some(Err(PerformError::StoreFull))
+        ));
+        assert!(matches!(store.remove(&ids[0]), Some(Ok(10))));
+    }
+
+    #[test]
+    fn least_recently_used_counts_reads_and_spares_pending_entries() {
+        let mut store = store_of(2, EvictionPolicy::LeastRecentlyUsed);
+        let ids = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+        store.insert(ids[0], Ok(0)).unwrap();
+        store.insert(ids[1], Ok(1)).unwrap();
+        assert!(matches!(store.peek(&ids[0]), Some(Ok(0))));
+        store.insert(ids[2], Ok(2)).unwrap();
+        assert!(store.contains(&ids[0]));
+        assert!(!store.contains(&ids[1]));
+
+        store.insert(ids[0], Err(PerformError::Empty)).unwrap();
+        assert!(store.is_ready(&ids[2]));
+        store.insert(ids[1], Ok(1)).unwrap();
+        assert!(store.contains(&ids[0]));
+        assert!(!store.contains(&ids[2]));
+    }
+
+    #[test]
+    fn oldest_completed_spares_pending_entries() {
+        let mut store = store_of(2, EvictionPolicy::OldestCompleted);
+        let ids = [
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        ];
+        store.insert(ids[0], Err(PerformError::Empty)).unwrap();
+        store.insert(ids[1], Ok(1)).unwrap();
+        store.insert(ids[2], Ok(2)).unwrap();
+        assert!(store.contains(&ids[0]));
+        assert!(!store.contains(&ids[1]));
+
+        store.insert(ids[2], Err(PerformError::Empty)).unwrap();
+        assert!(store.insert(ids[3], Ok(3)).is_err());
+    }
+
+    #[test]
+    fn oldest_completed_spares_held_entries() {
+        let mut store = Store::<u32>::new(PerformConfig {
+            capacity: Some(2),
+            eviction: EvictionPolicy::OldestCompleted,
+            completion_order: crate::CompletionOrder::Issued,
+            ..Default::default()
+        });
+        let ids = [
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        ];
+        store.issue(ids[0]);
+        let held = store.issue(ids[1]);
+        store
+            .insert_with(
+                ids[1],
+                Ok(1),
+                super::InsertOptions {
+                    sequence: held,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        store.insert(ids[2], Ok(2)).unwrap();
+        store.insert(ids[3], Ok(3)).unwrap();
+        assert!(store.contains(&ids[1]));
+        assert!(!store.contains(&ids[2]));
+    }
+
+    #[test]
+    fn unrepresentable_cool_downs_keep_the_circuit_open() {
+        let mut store = Store::<u32>::new(PerformConfig {
+            circuit_breaker: Some(CircuitBreaker {
+                threshold: 1,
+                cool_down: Duration::MAX,
+            }),
+            ..Default::default()
+        });
+        assert!(!store.is_circuit_open());
+        store.record_outcome(&Err(PerformError::TimedOut));
+        assert!(store.is_circuit_open());
+        store.reset_circuit();
+        assert!(!store.is_circuit_open());
+    }
+
+    #[test]
+    fn expired_results_are_not_handed_out() {
+        let mut store = Store::new(PerformConfig {
+            ttl: Some(Duration::ZERO),
+            ..Default::default()
+        });
+        let (first, second, pending) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        store.insert(first, Ok(1)).unwrap();
+        store.insert(second, Ok(2)).unwrap();
+        store.insert(pending, Err(PerformError::Empty)).unwrap();
+        assert!(matches!(
+            store.remove(&first),
+            Some(Err(PerformError::Expired))
+        ));
+
+        assert_eq!(store.purge_expired(), 1);
+        assert!(store.contains(&pending));
+        assert!(matches!(
+            store.remove(&second),
+            Some(Err(PerformError::Expired))
+        ));
+
+        let kept = Uuid::new_v4();
+        store
+            .insert_with_ttl(kept, Ok(3), Some(Duration::from_secs(60)))
+            .unwrap();
+        assert!(matches!(store.remove(&kept), Some(Ok(3))));
+
+        let forever = Uuid::new_v4();
+        store
+            .insert_with_ttl(forever, Ok(4), Some(Duration::MAX))
+            .unwrap();
+        assert_eq!(store.purge_expired(), 0);
+        assert!(matches!(store.remove(&forever), Some(Ok(4))));
+    }
+
+    #[test]
+    fn sweep_drops_orphaned_entries() {
+        let mut store = Store::<u32>::default();
+        let (alive, orphan) = (std::sync::Arc::new(()), std::sync::Arc::new(()));
+        let (kept, dropped) = (Uuid::new_v4(), Uuid::new_v4());
+        store.track(kept, std::sync::Arc::downgrade(&alive));
+        store.track(dropped, std::sync::Arc::downgrade(&orphan));
+        store.insert(kept, Ok(1)).unwrap();
+        store.insert(dropped, Ok(2)).unwrap();
+
+        drop(orphan);
+        assert_eq!(store.sweep(), 1);
+        assert!(store.contains(&kept));
+        assert!(!store.contains(&dropped));
+    }
+
+    #[test]
+    fn older_generations_do_not_clobber_newer_results() {
+        let mut store = Store::<u32>::default();
+        let id = Uuid::new_v4();
+        let newer = super::InsertOptions {
+            generation: 2,
+            ..Default::default()
+        };
+        let older = super::InsertOptions {
+            generation: 1,
+            ..Default::default()
+        };
+        store.insert_with(id, Ok(2), newer).unwrap();
+        assert!(matches!(
+            store.insert_with(id, Ok(1), older),
+            Err(PerformError::Superseded)
+        ));
+        assert!(matches!(store.remove_latest(&id, 2), Some(Ok(2))));
+
+        store.insert_with(id, Ok(1), older).unwrap();
+        assert!(store.remove_latest(&id, 2).is_none());
+        assert!(matches!(store.remove_latest(&id, 1), Some(Ok(1))));
+    }
+
+    #[test]
+    fn issued_order_holds_back_early_completions() {
+        let mut store = Store::<u32>::new(PerformConfig {
+            completion_order: crate::CompletionOrder::Issued,
+            ..Default::default()
+        });
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+        let first_sequence = store.issue(first);
+        let second_sequence = store.issue(second);
+        let options = |sequence| super::InsertOptions {
+            sequence,
+            ..Default::default()
+        };
+
+        store
+            .insert_with(second, Ok(2), options(second_sequence))
+            .unwrap();
+        assert!(store.remove(&second).is_none());
+        store
+            .insert_with(first, Ok(1), options(first_sequence))
+            .unwrap();
+        assert!(matches!(store.remove(&first), Some(Ok(1))));
+        assert!(matches!(store.remove(&second), Some(Ok(2))));
+    }
+
+    #[test]
+    fn heal_removes_entries_without_a_live_session() {
+        let mut store = Store::<u32>::new(PerformConfig {
+            completion_order: crate::CompletionOrder::Issued,
+            ..Default::default()
+        });
+        let session = std::sync::Arc::new(());
+        let (live, lost, untracked) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        store.track(live, std::sync::Arc::downgrade(&session));
+        store.track(lost, std::sync::Arc::downgrade(&std::sync::Arc::new(())));
+        store.insert(live, Ok(1)).unwrap();
+        store.insert(lost, Ok(2)).unwrap();
+        store.insert(untracked, Ok(3)).unwrap();
+        store.issue(lost);
+
+        let report = store.check();
+        assert_eq!(report.orphaned_entries, 2);
+        assert_eq!(report.stale_owners, 1);
+        assert_eq!(report.released_tickets, 1);
+        assert_eq!(store.heal(), report);
+        assert!(store.check().is_clean());
+        assert_eq!(store.len(), 1);
+        assert!(store.contains(&live));
+    }
+
+    #[test]
+    fn remove_if_leaves_unmatched_values_in_place() {
+        let mut store = Store::<u32>::default();
+        let id = Uuid::new_v4();
+        store.insert(id, Ok(7)).unwrap();
+        assert!(store.remove_if(&id, |value| *value == 8).is_none());
+        assert!(store.contains(&id));
+        assert!(matches!(
+            store.remove_if(&id, |value| *value == 7),
+            Some(Ok(7))
+        ));
+        assert!(!store.contains(&id));
+    }
+
+    #[test]
+    fn rate_limit_queues_and_coalesces_excess_performs() {
+        let mut store = Store::<u32>::new(PerformConfig {
+            rate_limit: Some(RateLimit {
+                overflow: RateLimitOverflow::Coalesce,
+                ..RateLimit::per_second(2)
+            }),
+            ..Default::default()
+        });
+        let id = Uuid::new_v4();
+        assert!(matches!(store.acquire(id, 1), Permit::Granted));
+        assert!(matches!(store.acquire(id, 2), Permit::Granted));
+        match store.acquire(id, 3) {
+            Permit::Wait(delay) => assert!(delay <= Duration::from_millis(500)),
+            _ => unreachable!(),
+        }
+        assert!(matches!(store.acquire(id, 4), Permit::Wait(_)));
+        assert!(matches!(store.acquire(id, 3), Permit::Coalesced));
+    }
+
+    #[test]
+    fn take_completed_drains_results_but_not_pending_markers() {
+        let mut store = store_of(1, EvictionPolicy::RejectNew);
+        let (done, pending, rejected) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        store.insert(done, Ok(1)).unwrap();
+        assert!(store.insert(rejected, Ok(3)).is_err());
+        store.set_config(PerformConfig::default());
+        store.insert(pending, Err(PerformError::Empty)).unwrap();
+
+        let mut completed = store.take_completed();
+        completed.sort_by_key(|(id, _)| *id != done);
+        assert_eq!(completed.len(), 2);
+        assert!(matches!(completed[0], (id, Ok(1)) if id == done));
+        assert!(matches!(completed[1], (id, Err(PerformError::StoreFull)) if id == rejected));
+        assert!(store.take_completed().is_empty());
+        assert!(store.contains(&pending));
+    }
+}
@@ -0,0 +1,222 @@
+use crate::{PerformError, Uuid};
+use futures::channel::mpsc;
+use futures::stream::{self, Stream};
+use std::sync::OnceLock;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+const EVENT_CAPACITY: usize = 64;
+
+type Event<T> = (Uuid, Result<T, PerformError>);
+
+#[derive(Debug, Clone)]
+pub struct Completion<T> {
+    pub id: Uuid,
+    pub task_id: u64,
+    pub result: Result<T, PerformError>,
+}
+
+#[derive(Debug, Clone)]
+pub enum StoreEvent<T> {
+    Completed(Uuid, Result<T, PerformError>),
+    Lagged(u64),
+}
+
+pub(super) struct Events<T> {
+    sender: std::sync::Mutex<broadcast::Sender<Event<T>>>,
+    taps: std::sync::Mutex<Vec<mpsc::UnboundedSender<Completion<T>>>>,
+    cloner: OnceLock<fn(&T) -> T>,
+}
+
+impl<T> Events<T> {
+    pub(super) fn new(capacity: Option<usize>) -> Self {
+        Self {
+            sender: std::sync::Mutex::new(broadcast::Sender::new(
+                capacity.unwrap_or(EVENT_CAPACITY),
+            )),
+            taps: Default::default(),
+            cloner: OnceLock::new(),
+        }
+    }
+
+    fn sender(&self) -> std::sync::MutexGuard<'_, broadcast::Sender<Event<T>>> {
+        self.sender
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+    fn taps(&self) -> std::sync::MutexGuard<'_, Vec<mpsc::UnboundedSender<Completion<T>>>> {
+        self.taps
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+    pub(super) fn subscribe(&self) -> impl Stream<Item = StoreEvent<T>>
+    where
+        T: Clone,
+    {
+        self.cloner.get_or_init(|| T::clone);
+        stream::unfold(self.sender().subscribe(), |mut events| async move {
+            let event = match events.recv().await {
+                Ok((id, result)) => StoreEvent::Completed(id, result),
+                Err(RecvError::Lagged(missed)) => {
+                    log::warn!("store subscriber lagged, {} completions dropped", missed);
+                    StoreEvent::Lagged(missed)
+                }
+                Err(RecvError::Closed) => return None,
+            };
+            Some((event, events))
+        })
+    }
+    pub(super) fn completions(&self) -> mpsc::UnboundedReceiver<Completion<T>>
+    where
+        T: Clone,
+    {
+        self.cloner.get_or_init(|| T::clone);
+        let (sender, receiver) = mpsc::unbounded();
+        self.taps().push(sender);
+        receiver
+    }
+    pub(super) fn publish<F>(
+        &self,
+        id: Uuid,
+        result: Result<T, PerformError>,
+        insert: F,
+    ) -> Result<(), PerformError>
+    where
+        F: FnOnce(Result<T, PerformError>) -> Result<u64, PerformError>,
+    {
+        let sender = self.sender().clone();
+        let copy = |clone: &fn(&T) -> T| result.as_ref().map(clone).map_err(Clone::clone);
+        let event = self
+            .cloner
+            .get()
+            .filter(|_| sender.receiver_count() > 0)
+            .map(copy);
+        let tapped = self
+            .cloner
+            .get()
+            .filter(|_| !self.taps().is_empty())
+            .map(copy);
+        let task_id = insert(result)?;
+        if let Some(result) = tapped {
+            self.feed_taps(id, task_id, result);
+        }
+        if let Some(result) = event {
+            let _ = sender.send((id, result));
+        }
+        Ok(())
+    }
+    fn feed_taps(&self, id: Uuid, task_id: u64, result: Result<T, PerformError>) {
+        let clone = self.cloner.get().expect("taps register a cloner");
+        self.taps().retain(|tap| {
+            let result = result.as_ref().map(clone).map_err(Clone::clone);
+            tap.unbounded_send(Completion {
+                id,
+                task_id,
+                result,
+            })
+            .is_ok()
+        });
+    }
+    pub(super) fn resize(&self, capacity: Option<usize>) {
+        let mut sender = self.sender();
+        if sender.receiver_count() == 0 {
+            *sender = broadcast::Sender::new(capacity.unwrap_or(EVENT_CAPACITY));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod events {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn subscribe_streams_every_completion() {
+        use super::StoreEvent;
+        use crate::{Perform, PerformError, StoreProvider};
+        use futures::StreamExt;
+        let completions = events::Provider::shared().subscribe();
+        futures::pin_mut!(completions);
+        let (first, second) = (
+            events::Session::activate().await,
+            events::Session::activate().await,
+        );
+        first.perform(async { 1 }).await;
+        second
+            .perform_result(async { Err(PerformError::Cancelled) })
+            .await;
+        assert!(matches!(
+            completions.next().await,
+            Some(StoreEvent::Completed(id, Ok(1))) if id == *first.id()
+        ));
+        assert!(matches!(
+            completions.next().await,
+            Some(StoreEvent::Completed(id, Err(PerformError::Cancelled))) if id == *second.id()
+        ));
+        assert_eq!(first.take().await.unwrap(), 1);
+    }
+
+    mod bursts {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn lagging_subscribers_are_told_how_much_they_missed() {
+        use super::StoreEvent;
+        use crate::{Perform, PerformConfig, StoreProvider};
+        use futures::StreamExt;
+        bursts::Provider::configure(PerformConfig {
+            event_capacity: Some(2),
+            ..Default::default()
+        })
+        .await;
+        let completions = bursts::Provider::shared().subscribe();
+        futures::pin_mut!(completions);
+        let session = bursts::Session::activate().await;
+        for value in 0..5 {
+            session.perform(async move { value }).await;
+        }
+        assert!(matches!(
+            completions.next().await,
+            Some(StoreEvent::Lagged(3))
+        ));
+        assert!(matches!(
+            completions.next().await,
+            Some(StoreEvent::Completed(_, Ok(3)))
+        ));
+    }
+
+    mod journal {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn completion_taps_never_lag() {
+        use crate::{Perform, PerformConfig, StoreProvider};
+        use futures::StreamExt;
+        journal::Provider::configure(PerformConfig {
+            event_capacity: Some(2),
+            ..Default::default()
+        })
+        .await;
+        let completions = journal::Provider::shared().completions();
+        let session = journal::Session::activate().await;
+        for value in 0..5 {
+            session.perform(async move { value }).await;
+        }
+        let seen = completions.take(5).collect::<Vec<_>>().await;
+        assert!(seen.iter().all(|completion| completion.id == *session.id()));
+        assert!(seen
+            .windows(2)
+            .all(|pair| pair[0].task_id < pair[1].task_id));
+        assert_eq!(
+            seen.into_iter()
+                .map(|completion| completion.result.unwrap())
+                .collect::<Vec<_>>(),
+            vec![0, 1, 2, 3, 4]
+        );
+    }
+}
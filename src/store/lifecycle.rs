@@ -0,0 +1,98 @@
+use super::Slot;
+use crate::CancellationToken;
+use futures::future::{self, Either};
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DrainReport {
+    pub completed: usize,
+    pub aborted: usize,
+}
+
+const DRAINING: usize = 1 << (usize::BITS - 1);
+
+#[derive(Default)]
+pub(super) struct Lifecycle {
+    state: AtomicUsize,
+    settled: Notify,
+    shutdown: std::sync::Mutex<CancellationToken>,
+    aborted: AtomicUsize,
+}
+
+impl Lifecycle {
+    pub(super) fn active(&self) -> usize {
+        self.state.load(Ordering::SeqCst) & !DRAINING
+    }
+    pub(super) fn is_draining(&self) -> bool {
+        self.state.load(Ordering::SeqCst) & DRAINING != 0
+    }
+    pub(super) fn enter(&self) -> Option<Slot<'_>> {
+        self.state
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |state| {
+                (state & DRAINING == 0).then_some(state + 1)
+            })
+            .ok()
+            .map(|_| Slot::new(&self.state, &self.settled))
+    }
+    fn shutdown(&self) -> CancellationToken {
+        self.shutdown
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+    pub(super) async fn until_shutdown<F: Future>(&self, fut: F) -> Option<F::Output> {
+        let token = self.shutdown();
+        let shutdown = token.cancelled();
+        futures::pin_mut!(fut, shutdown);
+        match future::select(fut, shutdown).await {
+            Either::Left((output, _)) => Some(output),
+            Either::Right(_) => {
+                self.aborted.fetch_add(1, Ordering::SeqCst);
+                None
+            }
+        }
+    }
+    pub(super) async fn drain(&self, deadline: Option<Duration>) -> DrainReport {
+        self.aborted.store(0, Ordering::SeqCst);
+        self.state.fetch_or(DRAINING, Ordering::SeqCst);
+        let pending = self.active();
+        let settled = async {
+            loop {
+                let settled = self.settled.notified();
+                if self.active() == 0 {
+                    return;
+                }
+                settled.await;
+            }
+        };
+        futures::pin_mut!(settled);
+        let drained = match deadline {
+            Some(deadline) => crate::time::timeout(deadline, settled.as_mut())
+                .await
+                .is_ok(),
+            None => {
+                settled.as_mut().await;
+                true
+            }
+        };
+        if !drained {
+            self.shutdown().cancel();
+            settled.await;
+        }
+        let aborted = self.aborted.load(Ordering::SeqCst);
+        DrainReport {
+            completed: pending.saturating_sub(aborted),
+            aborted,
+        }
+    }
+    pub(super) fn reopen(&self) {
+        *self
+            .shutdown
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = CancellationToken::new();
+        self.state.fetch_and(!DRAINING, Ordering::SeqCst);
+    }
+}
@@ -0,0 +1,121 @@
+use super::{SharedStore, Store};
+use crate::PerformError;
+#[cfg(target_arch = "wasm32")]
+use crate::PollEvent;
+use std::sync::atomic::Ordering;
+use tokio::sync::MutexGuard;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn try_lock_and_do_mut<T, R, F>(
+    shared: &'static SharedStore<T>,
+    f: F,
+) -> Result<R, PerformError>
+where
+    F: FnOnce(&mut Store<T>) -> Result<R, PerformError>,
+{
+    let mut attempts = shared.lock_retry.load(Ordering::Relaxed);
+    loop {
+        match shared.store.try_lock() {
+            Ok(mut store) => {
+                let output = f(&mut store);
+                return release(store, output);
+            }
+            Err(_) if attempts > 0 => {
+                attempts -= 1;
+                std::hint::spin_loop();
+            }
+            Err(_) => return Err(PerformError::Locked),
+        }
+    }
+}
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn try_lock_and_do_mut<T: 'static, R, F>(
+    shared: &'static SharedStore<T>,
+    f: F,
+) -> Result<R, PerformError>
+where
+    F: FnOnce(&mut Store<T>) -> Result<R, PerformError>,
+{
+    match shared.store.try_lock() {
+        Ok(mut store) => {
+            let output = f(&mut store);
+            release(store, output)
+        }
+        Err(_) => {
+            shared.retry_on_release();
+            Err(PerformError::Locked)
+        }
+    }
+}
+pub(crate) async fn lock_and_do_mut<T, R, F>(shared: &SharedStore<T>, f: F) -> R
+where
+    F: FnOnce(&mut Store<T>) -> R,
+{
+    let mut store = shared.store.lock().await;
+    let output = f(&mut store);
+    release(store, output)
+}
+pub(super) fn release<T, R>(mut store: MutexGuard<'_, Store<T>>, output: R) -> R {
+    let fired = store.take_fired();
+    drop(store);
+    fired.run();
+    output
+}
+
+#[cfg(target_arch = "wasm32")]
+impl<T: 'static> SharedStore<T> {
+    fn retry_on_release(&'static self) {
+        if self.lock_retry.load(Ordering::Relaxed) == 0
+            || self.retry_queued.swap(true, Ordering::SeqCst)
+        {
+            return;
+        }
+        crate::spawn_local(async move {
+            drop(self.store.lock().await);
+            self.retry_queued.store(false, Ordering::SeqCst);
+            self.completed.notify_waiters();
+            self.adapters.notify_all(PollEvent::Complete);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{try_lock_and_do_mut, SharedStore};
+    use crate::{PerformConfig, PerformError};
+
+    #[test]
+    fn lock_retry_gives_up_on_a_held_lock() {
+        let shared: &'static _ = Box::leak(Box::new(SharedStore::<u32>::new(PerformConfig {
+            lock_retry: 8,
+            ..Default::default()
+        })));
+        let held = shared.store().try_lock().unwrap();
+        let result = try_lock_and_do_mut(shared, |store| Ok(store.len()));
+        assert!(matches!(result, Err(PerformError::Locked)));
+        drop(held);
+        assert_eq!(
+            try_lock_and_do_mut(shared, |store| Ok(store.len())).unwrap(),
+            0
+        );
+    }
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    #[cfg(target_arch = "wasm32")]
+    async fn locked_attempts_are_retried_after_the_release() {
+        let shared: &'static _ = Box::leak(Box::new(SharedStore::<u32>::new(PerformConfig {
+            lock_retry: 1,
+            ..Default::default()
+        })));
+        let held = shared.store().try_lock().unwrap();
+        let released = shared.completed();
+        let result = try_lock_and_do_mut(shared, |store| Ok(store.len()));
+        assert!(matches!(result, Err(PerformError::Locked)));
+        drop(held);
+        released.await;
+        assert_eq!(
+            try_lock_and_do_mut(shared, |store| Ok(store.len())).unwrap(),
+            0
+        );
+    }
+}
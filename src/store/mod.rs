@@ -0,0 +1,331 @@
+mod admission;
+mod entries;
+mod events;
+mod lifecycle;
+mod lock;
+mod offline;
+
+use crate::adapter::Adapters;
+use crate::{async_trait, Mutex, PerformConfig, PerformError, PollEvent, Priority, Uuid};
+use admission::Admission;
+use futures::channel::mpsc;
+use futures::stream::Stream;
+use lifecycle::Lifecycle;
+use offline::Offline;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::time::Duration;
+use tokio::sync::futures::Notified;
+use tokio::sync::Notify;
+
+pub(crate) use admission::Slot;
+pub(crate) use entries::Permit;
+pub use entries::{HealReport, InsertOptions, Store};
+use events::Events;
+pub use events::{Completion, StoreEvent};
+pub use lifecycle::DrainReport;
+pub(crate) use lock::{lock_and_do_mut, try_lock_and_do_mut};
+
+pub type Completed<T> = Vec<(Uuid, Result<T, PerformError>)>;
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait StoreProvider: Send + Sync + 'static {
+    type Value: Send + 'static;
+
+    fn shared() -> &'static SharedStore<Self::Value>;
+    fn store() -> &'static Mutex<Store<Self::Value>> {
+        Self::shared().store()
+    }
+
+    fn spawn_reaper(interval: Duration) -> crate::Reaper
+    where
+        Self: Sized,
+    {
+        crate::Reaper::spawn::<Self>(interval)
+    }
+
+    async fn heal() -> HealReport {
+        lock_and_do_mut(Self::shared(), |store| store.heal()).await
+    }
+
+    async fn drain(deadline: Option<Duration>) -> DrainReport {
+        Self::shared().drain(deadline).await
+    }
+    fn reopen() {
+        Self::shared().reopen()
+    }
+
+    fn try_take_completed() -> Result<Completed<Self::Value>, PerformError> {
+        try_lock_and_do_mut(Self::shared(), |store| Ok(store.take_completed()))
+    }
+    async fn take_completed() -> Completed<Self::Value> {
+        lock_and_do_mut(Self::shared(), |store| store.take_completed()).await
+    }
+
+    fn try_configure(config: PerformConfig) -> Result<(), PerformError> {
+        Self::shared().try_configure(config)
+    }
+    async fn configure(config: PerformConfig) {
+        Self::shared().configure(config).await
+    }
+}
+
+pub struct SharedStore<T> {
+    store: Mutex<Store<T>>,
+    lock_retry: AtomicU32,
+    #[cfg(target_arch = "wasm32")]
+    retry_queued: AtomicBool,
+    deferred: AtomicBool,
+    completed: Notify,
+    dedup: std::sync::Mutex<HashMap<String, Waiters>>,
+    admission: Admission,
+    lifecycle: Lifecycle,
+    offline: Offline,
+    events: Events<T>,
+    adapters: Adapters,
+}
+
+pub(crate) type Waiters = Vec<(Uuid, InsertOptions)>;
+pub(crate) type Settled<T> = Vec<(Uuid, InsertOptions, Result<T, PerformError>)>;
+
+struct Unsettled<T: Send + 'static> {
+    shared: &'static SharedStore<T>,
+    settled: Settled<T>,
+}
+
+impl<T: Send + 'static> Drop for Unsettled<T> {
+    fn drop(&mut self) {
+        self.shared
+            .settle_detached(std::mem::take(&mut self.settled));
+    }
+}
+
+impl<T> Default for SharedStore<T> {
+    fn default() -> Self {
+        Self::new(PerformConfig::default())
+    }
+}
+
+impl<T> SharedStore<T> {
+    pub fn new(config: PerformConfig) -> Self {
+        Self {
+            lock_retry: AtomicU32::new(config.lock_retry),
+            #[cfg(target_arch = "wasm32")]
+            retry_queued: AtomicBool::new(false),
+            deferred: AtomicBool::new(config.deferred),
+            completed: Notify::new(),
+            dedup: Default::default(),
+            admission: Admission::new(config.max_in_flight),
+            lifecycle: Lifecycle::default(),
+            offline: Offline::new(config.queue_offline),
+            events: Events::new(config.event_capacity),
+            adapters: Adapters::default(),
+            store: Mutex::new(Store::new(config)),
+        }
+    }
+
+    pub fn store(&self) -> &Mutex<Store<T>> {
+        &self.store
+    }
+
+    pub(crate) fn adapters(&self) -> &Adapters {
+        &self.adapters
+    }
+    pub(crate) fn completed(&self) -> Notified<'_> {
+        self.completed.notified()
+    }
+    pub fn subscribe(&self) -> impl Stream<Item = StoreEvent<T>>
+    where
+        T: Clone,
+    {
+        self.events.subscribe()
+    }
+    pub fn completions(&self) -> mpsc::UnboundedReceiver<Completion<T>>
+    where
+        T: Clone,
+    {
+        self.events.completions()
+    }
+    fn insert_and_publish(
+        &self,
+        store: &mut Store<T>,
+        id: Uuid,
+        result: Result<T, PerformError>,
+        options: InsertOptions,
+    ) -> Result<(), PerformError> {
+        self.events.publish(id, result, |result| {
+            store.insert_with(id, result, options)?;
+            Ok(store.version(&id).unwrap_or_default())
+        })
+    }
+    pub(crate) async fn complete(
+        &self,
+        id: Uuid,
+        result: Result<T, PerformError>,
+        options: InsertOptions,
+    ) -> Result<(), PerformError> {
+        let inserted = lock_and_do_mut(self, |store| {
+            store.record_outcome(&result);
+            self.insert_and_publish(store, id, result, options)
+        })
+        .await;
+        self.completed.notify_waiters();
+        inserted
+    }
+
+    pub async fn hydrate(&self, id: Uuid, value: T) -> Result<(), PerformError> {
+        let hydrated = lock_and_do_mut(self, |store| store.hydrate(id, value)).await;
+        self.completed.notify_waiters();
+        self.adapters.notify(&id, PollEvent::Complete);
+        hydrated
+    }
+
+    pub(crate) fn join_dedup(&self, key: &str, id: Uuid, options: InsertOptions) -> bool {
+        let mut dedup = self
+            .dedup
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        match dedup.get_mut(key) {
+            Some(waiters) => {
+                waiters.push((id, options));
+                false
+            }
+            None => {
+                dedup.insert(key.to_string(), Vec::new());
+                true
+            }
+        }
+    }
+    pub(crate) fn leave_dedup(&self, key: &str) -> Waiters {
+        let mut dedup = self
+            .dedup
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        dedup.remove(key).unwrap_or_default()
+    }
+    pub(crate) async fn settle(&'static self, waiters: Waiters, result: Result<T, PerformError>)
+    where
+        T: Clone + Send + 'static,
+    {
+        let settled = waiters
+            .into_iter()
+            .map(|(id, options)| (id, options, result.clone()));
+        self.settle_each(settled).await
+    }
+    pub(crate) async fn settle_each<I>(&'static self, settled: I)
+    where
+        T: Send + 'static,
+        I: IntoIterator<Item = (Uuid, InsertOptions, Result<T, PerformError>)>,
+    {
+        let mut unsettled = Unsettled {
+            shared: self,
+            settled: settled.into_iter().collect(),
+        };
+        let mut store = self.store.lock().await;
+        for (id, options, result) in std::mem::take(&mut unsettled.settled) {
+            let _ = self.insert_and_publish(&mut store, id, result, options);
+        }
+        lock::release(store, ());
+        self.completed.notify_waiters();
+    }
+    pub(crate) fn settle_detached(&'static self, settled: Settled<T>)
+    where
+        T: Send + 'static,
+    {
+        if settled.is_empty() {
+            return;
+        }
+        let Ok(mut store) = self.store.try_lock() else {
+            let settle = self.settle_each(settled);
+            #[cfg(not(target_arch = "wasm32"))]
+            match self.is_deferred() {
+                true => crate::spawn::defer_shared(settle),
+                false => crate::spawn_portable(settle),
+            }
+            #[cfg(target_arch = "wasm32")]
+            match self.is_deferred() {
+                true => crate::spawn::defer(settle),
+                false => crate::spawn_portable(settle),
+            }
+            return;
+        };
+        for (id, options, result) in settled {
+            let _ = self.insert_and_publish(&mut store, id, result, options);
+        }
+        lock::release(store, ());
+        self.completed.notify_waiters();
+    }
+
+    pub fn is_deferred(&self) -> bool {
+        self.deferred.load(Ordering::Relaxed)
+    }
+    pub fn offline_pending(&self) -> usize {
+        self.offline.pending()
+    }
+    pub(crate) async fn wait_until_online(&self) {
+        self.offline.wait_until_online().await
+    }
+    pub fn in_flight(&self) -> usize {
+        self.admission.in_flight()
+    }
+    pub fn queued(&self) -> usize {
+        self.admission.queued()
+    }
+    pub(crate) fn try_acquire_slot(&self) -> Option<Slot<'_>> {
+        self.admission.try_acquire()
+    }
+    pub(crate) async fn acquire_slot(&self, priority: Priority) -> Slot<'_> {
+        self.admission.acquire(priority).await
+    }
+
+    pub fn active(&self) -> usize {
+        self.lifecycle.active()
+    }
+    pub fn is_draining(&self) -> bool {
+        self.lifecycle.is_draining()
+    }
+    pub(crate) fn enter(&self) -> Option<Slot<'_>> {
+        self.lifecycle.enter()
+    }
+    pub(crate) async fn until_shutdown<F: Future>(&self, fut: F) -> Option<F::Output> {
+        self.lifecycle.until_shutdown(fut).await
+    }
+    pub async fn drain(&self, deadline: Option<Duration>) -> DrainReport {
+        self.lifecycle.drain(deadline).await
+    }
+
+    pub fn reopen(&self) {
+        self.lifecycle.reopen()
+    }
+
+    pub fn try_configure(&'static self, config: PerformConfig) -> Result<(), PerformError> {
+        let limits = config.clone();
+        try_lock_and_do_mut(self, |store| {
+            store.set_config(config);
+            Ok(())
+        })?;
+        self.apply_limits(&limits);
+        Ok(())
+    }
+    pub async fn configure(&self, config: PerformConfig) {
+        self.apply_limits(&config);
+        lock_and_do_mut(self, |store| store.set_config(config)).await
+    }
+    fn apply_limits(&self, config: &PerformConfig) {
+        let PerformConfig {
+            lock_retry,
+            max_in_flight,
+            deferred,
+            queue_offline,
+            event_capacity,
+            ..
+        } = *config;
+        self.lock_retry.store(lock_retry, Ordering::Relaxed);
+        self.deferred.store(deferred, Ordering::Relaxed);
+        self.offline.set_queueing(queue_offline);
+        self.admission.set_limit(max_in_flight);
+        self.events.resize(event_capacity);
+    }
+}
@@ -0,0 +1,38 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+pub(super) struct Offline {
+    queue: AtomicBool,
+    pending: AtomicUsize,
+}
+
+impl Offline {
+    pub(super) fn new(queue: bool) -> Self {
+        Self {
+            queue: AtomicBool::new(queue),
+            pending: AtomicUsize::new(0),
+        }
+    }
+
+    pub(super) fn pending(&self) -> usize {
+        self.pending.load(Ordering::SeqCst)
+    }
+    pub(super) fn set_queueing(&self, queue: bool) {
+        self.queue.store(queue, Ordering::Relaxed);
+    }
+    pub(super) async fn wait_until_online(&self) {
+        if !self.queue.load(Ordering::Relaxed) || crate::connectivity::is_online() {
+            return;
+        }
+        self.pending.fetch_add(1, Ordering::SeqCst);
+        let _pending = Pending(&self.pending);
+        crate::connectivity::online().await
+    }
+}
+
+struct Pending<'a>(&'a AtomicUsize);
+
+impl Drop for Pending<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
@@ -0,0 +1,233 @@
+use crate::combinators::accumulate;
+use crate::session::Dispatch;
+use crate::store::lock_and_do_mut;
+use crate::{InsertOptions, Perform, PerformError, PerformHandle, Session, StoreProvider, Uuid};
+use futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use futures::future::join;
+use futures::stream::{Stream, StreamExt};
+use std::future::Future;
+
+pub struct PartialSender<T> {
+    sender: UnboundedSender<T>,
+}
+impl<T> Clone for PartialSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+        }
+    }
+}
+impl<T> PartialSender<T> {
+    pub fn send(&self, item: T) -> Result<(), PerformError> {
+        self.sender
+            .unbounded_send(item)
+            .map_err(|_| PerformError::Closed)
+    }
+}
+
+impl<P: StoreProvider> Session<P> {
+    pub async fn perform_stream<S>(&self, items: S)
+    where
+        S: Stream<Item = P::Value> + 'static,
+    {
+        let id = *self.id();
+        self.perform_staged(
+            move |options| latest::<P, _>(id, items, options),
+            Dispatch::default(),
+        )
+        .await
+    }
+    pub fn perform_stream_with_spawn_local<S>(&self, items: S) -> PerformHandle
+    where
+        S: Stream<Item = P::Value> + 'static,
+    {
+        let id = *self.id();
+        self.spawn_staged_with_spawn_local(
+            move |options| latest::<P, _>(id, items, options),
+            Dispatch::default(),
+        )
+    }
+}
+
+impl<P, T> Session<P>
+where
+    P: StoreProvider<Value = Vec<T>>,
+    T: 'static,
+{
+    pub async fn perform_stream_appending<S>(&self, items: S)
+    where
+        S: Stream<Item = T> + 'static,
+    {
+        let id = *self.id();
+        self.perform_staged(
+            move |options| accumulate::<P, _, _>(id, items, options),
+            Dispatch::default(),
+        )
+        .await
+    }
+    pub fn perform_stream_appending_with_spawn_local<S>(&self, items: S) -> PerformHandle
+    where
+        S: Stream<Item = T> + 'static,
+    {
+        let id = *self.id();
+        self.spawn_staged_with_spawn_local(
+            move |options| accumulate::<P, _, _>(id, items, options),
+            Dispatch::default(),
+        )
+    }
+    pub async fn perform_partial<F, Fut>(&self, f: F)
+    where
+        F: FnOnce(PartialSender<T>) -> Fut,
+        Fut: Future<Output = Vec<T>> + 'static,
+    {
+        let id = *self.id();
+        self.perform_staged(
+            move |options| {
+                let (sender, items) = mpsc::unbounded();
+                let fut = f(PartialSender { sender });
+                partial::<P, _, _>(id, fut, items, options)
+            },
+            Dispatch::default(),
+        )
+        .await
+    }
+    pub fn perform_partial_with_spawn_local<F, Fut>(&self, f: F) -> PerformHandle
+    where
+        F: FnOnce(PartialSender<T>) -> Fut + 'static,
+        Fut: Future<Output = Vec<T>> + 'static,
+    {
+        let id = *self.id();
+        self.spawn_staged_with_spawn_local(
+            move |options| {
+                let (sender, items) = mpsc::unbounded();
+                let fut = f(PartialSender { sender });
+                partial::<P, _, _>(id, fut, items, options)
+            },
+            Dispatch::default(),
+        )
+    }
+}
+
+async fn partial<P, T, Fut>(
+    id: Uuid,
+    fut: Fut,
+    items: UnboundedReceiver<T>,
+    options: InsertOptions,
+) -> (Result<Vec<T>, PerformError>, u32)
+where
+    P: StoreProvider<Value = Vec<T>>,
+    T: 'static,
+    Fut: Future<Output = Vec<T>>,
+{
+    match join(fut, accumulate::<P, _, _>(id, items, options)).await {
+        (tail, (Ok(mut rest), attempts)) => {
+            rest.extend(tail);
+            (Ok(rest), attempts)
+        }
+        (_, failed) => failed,
+    }
+}
+
+async fn latest<P, S>(
+    id: Uuid,
+    items: S,
+    options: InsertOptions,
+) -> (Result<P::Value, PerformError>, u32)
+where
+    P: StoreProvider,
+    S: Stream<Item = P::Value>,
+{
+    futures::pin_mut!(items);
+    while let Some(item) = items.next().await {
+        P::shared().settle_each([(id, options, Ok(item))]).await;
+    }
+    let last = lock_and_do_mut(P::shared(), |store| {
+        store.remove_latest(&id, options.generation)
+    })
+    .await;
+    (last.unwrap_or(Err(PerformError::Empty)), 1)
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use crate::{Perform, PerformError};
+    use futures::channel::{mpsc, oneshot};
+    use std::time::Duration;
+
+    mod ticks {
+        crate::build_perform!(u32);
+    }
+    mod lines {
+        crate::build_perform!(Vec<u32>);
+    }
+
+    async fn arrived(session: &lines::Session, count: usize) -> Vec<u32> {
+        let mut arrived = Vec::new();
+        while arrived.len() < count {
+            arrived.extend(session.take_partial().await);
+            tokio::task::yield_now().await;
+        }
+        arrived
+    }
+
+    #[tokio::test]
+    async fn perform_stream_keeps_the_latest_item() {
+        let session = ticks::Session::activate().await;
+        let (items, stream) = mpsc::unbounded();
+        let observed = async {
+            items.unbounded_send(1).unwrap();
+            let observed = session.try_take_timeout(Duration::from_secs(5)).await;
+            items.unbounded_send(2).unwrap();
+            items.unbounded_send(3).unwrap();
+            items.close_channel();
+            observed
+        };
+        let ((), observed) = tokio::join!(session.perform_stream(stream), observed);
+        assert_eq!(observed.unwrap(), 1);
+        assert_eq!(session.take().await.unwrap(), 3);
+
+        session.perform_stream(futures::stream::empty()).await;
+        assert!(matches!(session.take().await, Err(PerformError::Empty)));
+    }
+
+    #[tokio::test]
+    async fn perform_partial_exposes_pages_before_the_final_result() {
+        let session = lines::Session::activate().await;
+        let (resume, resumed) = oneshot::channel();
+        let partial = async {
+            let partial = arrived(&session, 2).await;
+            resume.send(()).unwrap();
+            partial
+        };
+        let ((), partial) = tokio::join!(
+            session.perform_partial(|pages| async move {
+                pages.send(1).unwrap();
+                pages.send(2).unwrap();
+                resumed.await.unwrap();
+                pages.send(3).unwrap();
+                vec![4]
+            }),
+            partial
+        );
+        assert_eq!(partial, vec![1, 2]);
+        assert_eq!(session.take().await.unwrap(), vec![3, 4]);
+    }
+
+    #[tokio::test]
+    async fn perform_stream_appending_buffers_items() {
+        let session = lines::Session::activate().await;
+        let (items, stream) = mpsc::unbounded();
+        let partial = async {
+            items.unbounded_send(1).unwrap();
+            items.unbounded_send(2).unwrap();
+            let partial = arrived(&session, 2).await;
+            items.unbounded_send(3).unwrap();
+            items.unbounded_send(4).unwrap();
+            items.close_channel();
+            partial
+        };
+        let ((), partial) = tokio::join!(session.perform_stream_appending(stream), partial);
+        assert_eq!(partial, vec![1, 2]);
+        assert_eq!(session.take().await.unwrap(), vec![3, 4]);
+    }
+}
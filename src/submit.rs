@@ -0,0 +1,113 @@
+use crate::{PerformError, Performer, StoreProvider};
+use std::future::Future;
+
+pub struct SubmitGuard<P: StoreProvider> {
+    performer: Performer<P>,
+    outcome: Option<Result<P::Value, PerformError>>,
+    swallowed: usize,
+}
+
+impl<P: StoreProvider> SubmitGuard<P> {
+    pub fn new(performer: Performer<P>) -> Self {
+        Self {
+            performer,
+            outcome: None,
+            swallowed: 0,
+        }
+    }
+    pub fn performer(&self) -> &Performer<P> {
+        &self.performer
+    }
+    pub fn performer_mut(&mut self) -> &mut Performer<P> {
+        &mut self.performer
+    }
+    pub fn can_submit(&self) -> bool {
+        !self.performer.is_pending()
+    }
+    pub fn swallowed(&self) -> usize {
+        self.swallowed
+    }
+    pub fn outcome(&self) -> Option<&Result<P::Value, PerformError>> {
+        self.outcome.as_ref()
+    }
+    pub fn take_outcome(&mut self) -> Option<Result<P::Value, PerformError>> {
+        self.outcome.take()
+    }
+    pub fn poll(&mut self) -> Option<&Result<P::Value, PerformError>> {
+        match self.performer.try_take() {
+            Err(PerformError::Empty | PerformError::Locked) => {}
+            taken => self.outcome = Some(taken),
+        }
+        self.outcome.as_ref()
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn submit<F>(&mut self, fut: F) -> bool
+    where
+        F: Future<Output = P::Value> + 'static + Send,
+    {
+        if !self.admit() {
+            return false;
+        }
+        self.performer.perform_latest_spawned(fut);
+        true
+    }
+    #[cfg(target_arch = "wasm32")]
+    pub fn submit<F>(&mut self, fut: F) -> bool
+    where
+        F: Future<Output = P::Value> + 'static,
+    {
+        self.submit_with_spawn_local(fut)
+    }
+    pub fn submit_with_spawn_local<F>(&mut self, fut: F) -> bool
+    where
+        F: Future<Output = P::Value> + 'static,
+    {
+        if !self.admit() {
+            return false;
+        }
+        self.performer.perform_latest_with_spawn_local(fut);
+        true
+    }
+    fn admit(&mut self) -> bool {
+        if !self.can_submit() {
+            self.swallowed += 1;
+            return false;
+        }
+        self.performer.reset();
+        self.outcome = None;
+        true
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::SubmitGuard;
+    use crate::Perform;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    mod form {
+        crate::build_perform!(u32);
+    }
+
+    #[tokio::test]
+    async fn double_submits_are_swallowed_while_in_flight() {
+        let mut guard = SubmitGuard::new(form::Performer::new(form::Session::activate().await));
+        let posts = Arc::new(AtomicU32::new(0));
+        for _ in 0..3 {
+            let posts = posts.clone();
+            guard.submit(async move {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                posts.fetch_add(1, Ordering::SeqCst)
+            });
+        }
+        assert!(!guard.can_submit());
+        assert_eq!(guard.swallowed(), 2);
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert!(matches!(guard.poll(), Some(Ok(0))));
+        assert!(matches!(guard.poll(), Some(Ok(0))));
+        assert!(guard.can_submit());
+        assert_eq!(posts.load(Ordering::SeqCst), 1);
+    }
+}
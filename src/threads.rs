@@ -0,0 +1,141 @@
+use crate::{PerformHandle, Session, StoreProvider};
+use std::future::Future;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionMode {
+    Threads,
+    Local,
+}
+
+#[cfg(target_arch = "wasm32")]
+mod browser {
+    use super::ExecutionMode;
+    use futures::future::BoxFuture;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use wasm_bindgen::JsValue;
+
+    type ThreadSpawner = Rc<dyn Fn(BoxFuture<'static, ()>)>;
+
+    thread_local! {
+        static SPAWNER: RefCell<Option<ThreadSpawner>> = const { RefCell::new(None) };
+    }
+
+    /// Installs the thread pool `perform_threaded` sends work to. The crate does not ship
+    /// one: start your own pool of shared-memory workers (for example with
+    /// `wasm-bindgen-rayon`) and pass a closure that hands each future to it. Only the
+    /// first call wins.
+    pub fn set_thread_spawner<S>(spawner: S) -> bool
+    where
+        S: Fn(BoxFuture<'static, ()>) + 'static,
+    {
+        SPAWNER.with(|installed| {
+            let mut installed = installed.borrow_mut();
+            if installed.is_some() {
+                return false;
+            }
+            *installed = Some(Rc::new(spawner));
+            true
+        })
+    }
+
+    pub fn is_cross_origin_isolated() -> bool {
+        let global = js_sys::global();
+        let isolated = js_sys::Reflect::get(&global, &JsValue::from_str("crossOriginIsolated"))
+            .ok()
+            .and_then(|value| value.as_bool())
+            .unwrap_or(false);
+        isolated
+            && js_sys::Reflect::has(&global, &JsValue::from_str("SharedArrayBuffer"))
+                .unwrap_or(false)
+    }
+
+    pub(crate) fn thread_spawner() -> Option<ThreadSpawner> {
+        if !cfg!(target_feature = "atomics") || !is_cross_origin_isolated() {
+            return None;
+        }
+        SPAWNER.with(|installed| installed.borrow().clone())
+    }
+
+    pub fn execution_mode() -> ExecutionMode {
+        match thread_spawner() {
+            Some(_) => ExecutionMode::Threads,
+            None => ExecutionMode::Local,
+        }
+    }
+}
+#[cfg(target_arch = "wasm32")]
+pub(crate) use browser::thread_spawner;
+#[cfg(target_arch = "wasm32")]
+pub use browser::{execution_mode, is_cross_origin_isolated, set_thread_spawner};
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn execution_mode() -> ExecutionMode {
+    use tokio::runtime::{Handle, RuntimeFlavor};
+    if crate::spawn::has_spawner() {
+        return ExecutionMode::Threads;
+    }
+    match Handle::try_current().map(|handle| handle.runtime_flavor()) {
+        Ok(RuntimeFlavor::MultiThread) => ExecutionMode::Threads,
+        _ => ExecutionMode::Local,
+    }
+}
+
+impl<P: StoreProvider> Session<P> {
+    #[cfg(target_arch = "wasm32")]
+    pub fn perform_threaded<Fut>(&self, fut: Fut) -> PerformHandle
+    where
+        Fut: Future<Output = P::Value> + 'static + Send,
+    {
+        use futures::FutureExt;
+        match thread_spawner() {
+            Some(spawn) => {
+                let (sender, receiver) = futures::channel::oneshot::channel();
+                spawn(
+                    async move {
+                        let _ = sender.send(fut.await);
+                    }
+                    .boxed(),
+                );
+                self.perform_result_with_spawn_local(async move {
+                    receiver.await.map_err(|_| crate::PerformError::Cancelled)
+                })
+            }
+            None => self.perform_with_spawn_local(fut),
+        }
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn perform_threaded<Fut>(&self, fut: Fut) -> PerformHandle
+    where
+        Fut: Future<Output = P::Value> + 'static + Send,
+    {
+        self.perform_spawned(fut)
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::{execution_mode, ExecutionMode};
+    use crate::Perform;
+    use std::time::Duration;
+
+    mod threaded {
+        crate::build_perform!(u64);
+    }
+
+    #[tokio::test]
+    async fn current_thread_runtimes_report_local() {
+        if !crate::spawn::has_spawner() {
+            assert_eq!(execution_mode(), ExecutionMode::Local);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn multi_thread_runtimes_report_threads() {
+        assert_eq!(execution_mode(), ExecutionMode::Threads);
+        let session = threaded::Session::activate().await;
+        session.perform_threaded(async { (1..=20u64).product::<u64>() % 1_000_007 });
+        let taken = session.try_take_timeout(Duration::from_secs(5)).await;
+        assert_eq!(taken.unwrap(), (1..=20u64).product::<u64>() % 1_000_007);
+    }
+}
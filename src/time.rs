@@ -0,0 +1,187 @@
+use crate::PerformError;
+use futures::future::{self, Either};
+use futures::Stream;
+use std::future::Future;
+use std::ops::ControlFlow;
+use std::time::Duration;
+
+pub use web_time::{Instant, SystemTime};
+
+#[cfg(target_arch = "wasm32")]
+const TIMER_LIMIT: Duration = Duration::from_millis(i32::MAX as u64);
+
+#[cfg(target_arch = "wasm32")]
+pub async fn sleep(mut duration: Duration) {
+    while duration > TIMER_LIMIT {
+        gloo_timers::future::sleep(TIMER_LIMIT).await;
+        duration -= TIMER_LIMIT;
+    }
+    gloo_timers::future::sleep(duration).await
+}
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await
+}
+
+#[cfg(target_arch = "wasm32")]
+pub async fn yield_now() {
+    gloo_timers::future::sleep(Duration::ZERO).await
+}
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn yield_now() {
+    tokio::task::yield_now().await
+}
+
+pub(crate) async fn chunked<F, T>(mut step: F, budget: Duration) -> T
+where
+    F: FnMut() -> ControlFlow<T>,
+{
+    loop {
+        let started = Instant::now();
+        loop {
+            if let ControlFlow::Break(value) = step() {
+                return value;
+            }
+            if started.elapsed() >= budget {
+                break;
+            }
+        }
+        yield_now().await;
+    }
+}
+
+pub async fn sleep_until(deadline: Instant) {
+    sleep(deadline.saturating_duration_since(Instant::now())).await
+}
+
+pub async fn delay<F: Future>(duration: Duration, fut: F) -> F::Output {
+    sleep(duration).await;
+    fut.await
+}
+
+pub async fn deadline_at<F: Future>(deadline: Instant, fut: F) -> Result<F::Output, PerformError> {
+    let expired = sleep_until(deadline);
+    futures::pin_mut!(fut, expired);
+    match future::select(fut, expired).await {
+        Either::Left((output, _)) => Ok(output),
+        Either::Right(_) => Err(PerformError::TimedOut),
+    }
+}
+
+pub async fn timeout<F: Future>(duration: Duration, fut: F) -> Result<F::Output, PerformError> {
+    match Instant::now().checked_add(duration) {
+        Some(deadline) => deadline_at(deadline, fut).await,
+        None => Ok(fut.await),
+    }
+}
+
+pub fn interval(period: Duration) -> impl Stream<Item = Instant> {
+    futures::stream::unfold(Instant::now(), move |next| async move {
+        sleep_until(next).await;
+        let now = Instant::now();
+        Some((now, (next + period).max(now)))
+    })
+}
+
+#[cfg(all(target_arch = "wasm32", feature = "web-sys"))]
+pub(crate) async fn idle(timeout: Duration) {
+    use wasm_bindgen::closure::Closure;
+    use wasm_bindgen::JsCast;
+
+    let Some(window) = web_sys::window() else {
+        return sleep(Duration::ZERO).await;
+    };
+    let (sender, receiver) = futures::channel::oneshot::channel();
+    let callback = Closure::once(move || {
+        let _ = sender.send(());
+    });
+    let options = web_sys::IdleRequestOptions::new();
+    options.set_timeout(timeout.as_millis().min(u32::MAX as u128) as u32);
+    let requested =
+        window.request_idle_callback_with_options(callback.as_ref().unchecked_ref(), &options);
+    match requested {
+        Ok(handle) => {
+            let _pending = IdleRequest { window, handle };
+            let _ = receiver.await;
+        }
+        Err(_) => sleep(Duration::ZERO).await,
+    }
+}
+#[cfg(all(not(target_arch = "wasm32"), feature = "web-sys"))]
+pub(crate) async fn idle(_timeout: Duration) {
+    tokio::task::yield_now().await
+}
+
+#[cfg(all(target_arch = "wasm32", feature = "web-sys"))]
+struct IdleRequest {
+    window: web_sys::Window,
+    handle: u32,
+}
+
+#[cfg(all(target_arch = "wasm32", feature = "web-sys"))]
+impl Drop for IdleRequest {
+    fn drop(&mut self) {
+        self.window.cancel_idle_callback(self.handle);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(not(target_arch = "wasm32"))]
+    use super::Instant;
+    use super::SystemTime;
+    #[cfg(not(target_arch = "wasm32"))]
+    use std::time::Duration;
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn sleep_and_yield_are_portable() {
+        let started = Instant::now();
+        super::sleep(Duration::from_millis(10)).await;
+        assert!(started.elapsed() >= Duration::from_millis(10));
+        super::yield_now().await;
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn deadlines_and_delays_share_one_clock() {
+        let started = Instant::now();
+        let late = super::deadline_at(started + Duration::from_millis(10), async {
+            super::sleep(Duration::from_millis(200)).await;
+        });
+        assert!(matches!(late.await, Err(crate::PerformError::TimedOut)));
+        let delayed = super::delay(Duration::from_millis(10), async { 7 });
+        assert_eq!(
+            super::timeout(Duration::from_secs(1), delayed)
+                .await
+                .unwrap(),
+            7
+        );
+        assert!(started.elapsed() >= Duration::from_millis(20));
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn unrepresentable_timeouts_never_expire() {
+        assert_eq!(super::timeout(Duration::MAX, async { 7 }).await.unwrap(), 7);
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn interval_ticks_immediately_then_every_period() {
+        use futures::StreamExt;
+        let started = Instant::now();
+        let ticks = super::interval(Duration::from_millis(10))
+            .take(3)
+            .collect::<Vec<_>>()
+            .await;
+        assert!(ticks[0] - started < Duration::from_millis(10));
+        assert!(ticks[2] - started >= Duration::from_millis(20));
+    }
+
+    #[test]
+    fn wall_clock_is_past_the_epoch() {
+        let now = SystemTime::now();
+        assert!(now.duration_since(SystemTime::UNIX_EPOCH).is_ok());
+    }
+}
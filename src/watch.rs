@@ -0,0 +1,102 @@
+use crate::store::{lock_and_do_mut, try_lock_and_do_mut};
+use crate::{Perform, PerformError, Session, StoreProvider, Uuid};
+use std::marker::PhantomData;
+
+pub struct Watch<P> {
+    id: Uuid,
+    seen: Option<u64>,
+    provider: PhantomData<P>,
+}
+impl<P> Clone for Watch<P> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            seen: self.seen,
+            provider: PhantomData,
+        }
+    }
+}
+impl<P: StoreProvider> Watch<P>
+where
+    P::Value: Clone,
+{
+    pub fn get_latest(&self) -> Result<P::Value, PerformError> {
+        try_lock_and_do_mut(P::shared(), |store| {
+            store.peek(&self.id).unwrap_or(Err(PerformError::Empty))
+        })
+    }
+    pub fn has_changed(&self) -> bool {
+        P::shared()
+            .store()
+            .try_lock()
+            .is_ok_and(|store| store.version(&self.id) > self.seen)
+    }
+    pub fn mark_seen(&mut self) -> Result<P::Value, PerformError> {
+        try_lock_and_do_mut(P::shared(), |store| {
+            self.seen = store.version(&self.id);
+            store.peek(&self.id).unwrap_or(Err(PerformError::Empty))
+        })
+    }
+    pub async fn changed(&mut self) -> Result<P::Value, PerformError> {
+        loop {
+            let completed = P::shared().completed();
+            let latest = lock_and_do_mut(P::shared(), |store| match store.version(&self.id) {
+                version if version > self.seen => {
+                    self.seen = version;
+                    store.peek(&self.id)
+                }
+                _ => None,
+            })
+            .await;
+            if let Some(result) = latest {
+                return result;
+            }
+            completed.await;
+        }
+    }
+}
+
+impl<P: StoreProvider> Session<P>
+where
+    P::Value: Clone,
+{
+    pub fn watch(&self) -> Watch<P> {
+        Watch {
+            id: *self.id(),
+            seen: None,
+            provider: PhantomData,
+        }
+    }
+    pub fn get_latest(&self) -> Result<P::Value, PerformError> {
+        self.watch().get_latest()
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use crate::Perform;
+
+    mod shared {
+        crate::build_perform!(String);
+    }
+
+    #[tokio::test]
+    async fn watchers_read_the_latest_value_without_consuming_it() {
+        let session = shared::Session::activate().await;
+        let (mut left, mut right) = (session.watch(), session.watch());
+        assert!(!left.has_changed());
+
+        session.perform(async { "first".to_string() }).await;
+        assert!(left.has_changed());
+        assert_eq!(left.changed().await.unwrap(), "first");
+        assert_eq!(right.changed().await.unwrap(), "first");
+        assert!(!left.has_changed());
+        assert_eq!(session.get_latest().unwrap(), "first");
+
+        let waiting = tokio::spawn(async move { left.changed().await });
+        session.perform(async { "second".to_string() }).await;
+        assert_eq!(waiting.await.unwrap().unwrap(), "second");
+        assert_eq!(right.get_latest().unwrap(), "second");
+        assert_eq!(session.take().await.unwrap(), "second");
+    }
+}
@@ -0,0 +1,560 @@
+use crate::{Error, PerformError, RemoteError, TaskMessage};
+use serde::de::{DeserializeOwned, Error as _};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+pub trait WorkerTask: Serialize + DeserializeOwned + 'static {
+    const NAME: &'static str;
+    type Output: Serialize + DeserializeOwned + 'static;
+
+    fn run(self) -> Result<Self::Output, PerformError>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("no worker pool installed")]
+pub struct NoWorkerPool;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkerRequest<T> {
+    pub id: u64,
+    pub task: String,
+    pub input: T,
+}
+
+#[derive(Debug, Clone)]
+pub struct WorkerResponse<T> {
+    pub id: u64,
+    pub task: String,
+    pub result: Result<T, PerformError>,
+}
+
+impl<T: WorkerTask> WorkerRequest<T> {
+    pub fn new(id: u64, input: T) -> Self {
+        Self {
+            id,
+            task: T::NAME.to_string(),
+            input,
+        }
+    }
+
+    pub fn respond(self) -> WorkerResponse<T::Output> {
+        WorkerResponse {
+            id: self.id,
+            task: self.task,
+            result: self.input.run(),
+        }
+    }
+}
+
+impl<T> WorkerResponse<T> {
+    pub fn failed(id: u64, task: impl Into<String>, error: PerformError) -> Self {
+        Self {
+            id,
+            task: task.into(),
+            result: Err(error),
+        }
+    }
+
+    pub fn unknown_task(id: u64, task: &str) -> Self {
+        let error = protocol_error(format!("unknown worker task: {task}"));
+        Self::failed(id, task, error)
+    }
+}
+
+impl<T: Serialize> Serialize for WorkerRequest<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        TaskMessage::for_task(self.id, self.task.as_str(), Ok(&self.input)).serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for WorkerRequest<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let message = TaskMessage::<T>::deserialize(deserializer)?;
+        let (id, task) = (message.task_id, message.task.clone());
+        let input = message.into_result().map_err(D::Error::custom)?;
+        Ok(Self { id, task, input })
+    }
+}
+
+impl<T: Serialize> Serialize for WorkerResponse<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let result = self.result.as_ref().map_err(Clone::clone);
+        TaskMessage::for_task(self.id, self.task.as_str(), result).serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for WorkerResponse<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let message = TaskMessage::<T>::deserialize(deserializer)?;
+        Ok(Self {
+            id: message.task_id,
+            task: message.task.clone(),
+            result: message.into_result(),
+        })
+    }
+}
+
+pub(crate) fn protocol_error(message: impl ToString) -> PerformError {
+    PerformError::task(RemoteError {
+        message: message.to_string(),
+    })
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    pub workers: usize,
+    pub alive: usize,
+    pub in_flight: usize,
+    pub submitted: u64,
+    pub completed: u64,
+    pub failed: u64,
+}
+
+#[cfg(any(target_arch = "wasm32", test))]
+fn least_loaded(loads: &[Option<usize>], start: usize) -> Option<usize> {
+    (0..loads.len())
+        .map(|offset| (start + offset) % loads.len())
+        .filter_map(|index| loads[index].map(|load| (load, index)))
+        .min_by_key(|(load, _)| *load)
+        .map(|(_, index)| index)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<P: crate::StoreProvider> crate::Session<P> {
+    pub fn perform_blocking_task<T>(&self, task: T) -> crate::PerformHandle
+    where
+        T: WorkerTask<Output = P::Value> + Send,
+    {
+        self.perform_result_spawned(async move {
+            tokio::task::spawn_blocking(|| task.run())
+                .await
+                .map_err(PerformError::task)?
+        })
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod browser {
+    use super::{
+        least_loaded, protocol_error, NoWorkerPool, PoolStats, WorkerRequest, WorkerResponse,
+        WorkerTask,
+    };
+    use crate::{PerformError, PerformHandle, Session, StoreProvider, TaskMessage};
+    use futures::channel::oneshot;
+    use serde::de::IgnoredAny;
+    use std::cell::{Cell, RefCell};
+    use std::collections::HashMap;
+    use std::future::Future;
+    use std::rc::Rc;
+    use wasm_bindgen::closure::Closure;
+    use wasm_bindgen::{JsCast, JsValue};
+    use web_sys::{DedicatedWorkerGlobalScope, MessageEvent, Worker, WorkerOptions, WorkerType};
+
+    type Message = (JsValue, js_sys::Array);
+    type Handler = Box<dyn Fn(u64, JsValue) -> Message>;
+    type Pending = Rc<RefCell<HashMap<u64, oneshot::Sender<JsValue>>>>;
+
+    fn to_message<T: serde::Serialize>(value: &T) -> Result<Message, PerformError> {
+        let (message, transfer) =
+            crate::bytes::collect_transfers(|| serde_wasm_bindgen::to_value(value));
+        Ok((message.map_err(protocol_error)?, transfer))
+    }
+
+    fn failure_message(id: u64, task: &str, error: PerformError) -> Message {
+        to_message(&WorkerResponse::<()>::failed(id, task, error))
+            .unwrap_or_else(|_| (JsValue::NULL, js_sys::Array::new()))
+    }
+
+    fn from_js<T: serde::de::DeserializeOwned>(value: JsValue) -> Result<T, PerformError> {
+        serde_wasm_bindgen::from_value(value).map_err(protocol_error)
+    }
+
+    #[derive(Default)]
+    pub struct WorkerServer {
+        handlers: HashMap<&'static str, Handler>,
+    }
+
+    impl WorkerServer {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        pub fn register<T: WorkerTask>(mut self) -> Self {
+            let handler = |id, data| {
+                let response = match from_js::<WorkerRequest<T>>(data) {
+                    Ok(request) => request.respond(),
+                    Err(error) => WorkerResponse::failed(id, T::NAME, error),
+                };
+                to_message(&response).unwrap_or_else(|error| failure_message(id, T::NAME, error))
+            };
+            self.handlers.insert(T::NAME, Box::new(handler));
+            self
+        }
+
+        pub fn serve(self) -> Result<(), PerformError> {
+            let scope = js_sys::global()
+                .dyn_into::<DedicatedWorkerGlobalScope>()
+                .map_err(|_| protocol_error("not running inside a dedicated worker"))?;
+            let reply = scope.clone();
+            let callback = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+                let data = event.data();
+                let Ok(header) = from_js::<TaskMessage<IgnoredAny>>(data.clone()) else {
+                    return;
+                };
+                let (id, task) = (header.task_id, header.task);
+                let response = match self.handlers.get(task.as_str()) {
+                    Some(handler) => handler(id, data),
+                    None => {
+                        let response = WorkerResponse::<()>::unknown_task(id, &task);
+                        to_message(&response)
+                            .unwrap_or_else(|error| failure_message(id, &task, error))
+                    }
+                };
+                let _ = reply.post_message_with_transfer(&response.0, &response.1);
+            });
+            scope.set_onmessage(Some(callback.as_ref().unchecked_ref()));
+            callback.forget();
+            Ok(())
+        }
+    }
+
+    struct Inner {
+        worker: Worker,
+        alive: Cell<bool>,
+        next_id: Cell<u64>,
+        pending: Pending,
+        _on_message: Closure<dyn FnMut(MessageEvent)>,
+        _on_error: Closure<dyn FnMut()>,
+    }
+
+    impl Inner {
+        fn shutdown(&self) {
+            if self.alive.replace(false) {
+                self.worker.set_onmessage(None);
+                self.worker.set_onerror(None);
+                self.worker.terminate();
+                self.pending.borrow_mut().clear();
+            }
+        }
+    }
+
+    impl Drop for Inner {
+        fn drop(&mut self) {
+            self.shutdown();
+        }
+    }
+
+    #[derive(Clone)]
+    pub struct WorkerHandle {
+        inner: Rc<Inner>,
+    }
+
+    impl WorkerHandle {
+        pub fn spawn(script_url: &str) -> Result<Self, PerformError> {
+            let options = WorkerOptions::new();
+            options.set_type(WorkerType::Module);
+            let worker = Worker::new_with_options(script_url, &options)
+                .map_err(|error| protocol_error(format!("{error:?}")))?;
+
+            let pending = Pending::default();
+            let on_message = Closure::<dyn FnMut(MessageEvent)>::new({
+                let pending = pending.clone();
+                move |event: MessageEvent| {
+                    let data = event.data();
+                    if let Ok(header) = from_js::<TaskMessage<IgnoredAny>>(data.clone()) {
+                        if let Some(sender) = pending.borrow_mut().remove(&header.task_id) {
+                            let _ = sender.send(data);
+                        }
+                    }
+                }
+            });
+            let on_error = Closure::<dyn FnMut()>::new({
+                let pending = pending.clone();
+                move || pending.borrow_mut().clear()
+            });
+            worker.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+            worker.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+
+            Ok(Self {
+                inner: Rc::new(Inner {
+                    worker,
+                    alive: Cell::new(true),
+                    next_id: Cell::new(0),
+                    pending,
+                    _on_message: on_message,
+                    _on_error: on_error,
+                }),
+            })
+        }
+
+        pub fn is_alive(&self) -> bool {
+            self.inner.alive.get()
+        }
+
+        pub fn in_flight(&self) -> usize {
+            self.inner.pending.borrow().len()
+        }
+
+        pub fn terminate(&self) {
+            self.inner.shutdown();
+        }
+
+        pub fn run<T: WorkerTask>(
+            &self,
+            task: T,
+        ) -> impl Future<Output = Result<T::Output, PerformError>> + 'static {
+            let inner = self.inner.clone();
+            async move {
+                if !inner.alive.get() {
+                    return Err(PerformError::Closed);
+                }
+                let id = inner.next_id.get();
+                inner.next_id.set(id.wrapping_add(1));
+                let (message, transfer) = to_message(&WorkerRequest::new(id, task))?;
+
+                let (sender, receiver) = oneshot::channel();
+                inner.pending.borrow_mut().insert(id, sender);
+                if inner
+                    .worker
+                    .post_message_with_transfer(&message, &transfer)
+                    .is_err()
+                {
+                    inner.pending.borrow_mut().remove(&id);
+                    return Err(PerformError::Closed);
+                }
+                let data = receiver.await.map_err(|_| PerformError::Closed)?;
+                from_js::<WorkerResponse<T::Output>>(data)?.result
+            }
+        }
+    }
+
+    impl<P: StoreProvider> Session<P> {
+        pub fn perform_on_worker<T>(&self, worker: &WorkerHandle, task: T) -> PerformHandle
+        where
+            T: WorkerTask<Output = P::Value>,
+        {
+            self.perform_result_with_spawn_local(worker.run(task))
+        }
+
+        pub fn perform_on_pool<T>(&self, pool: &WorkerPool, task: T) -> PerformHandle
+        where
+            T: WorkerTask<Output = P::Value>,
+        {
+            self.perform_result_with_spawn_local(pool.run(task))
+        }
+
+        pub fn perform_blocking_task<T>(&self, task: T) -> PerformHandle
+        where
+            T: WorkerTask<Output = P::Value>,
+        {
+            match WorkerPool::installed() {
+                Some(pool) => self.perform_on_pool(&pool, task),
+                None => self.perform_result_with_spawn_local(async {
+                    Err(PerformError::task(NoWorkerPool))
+                }),
+            }
+        }
+    }
+
+    thread_local! {
+        static INSTALLED: RefCell<Option<WorkerPool>> = const { RefCell::new(None) };
+    }
+
+    #[derive(Default)]
+    struct Counters {
+        cursor: Cell<usize>,
+        submitted: Cell<u64>,
+        completed: Cell<u64>,
+        failed: Cell<u64>,
+    }
+
+    #[derive(Clone)]
+    pub struct WorkerPool {
+        workers: Rc<Vec<WorkerHandle>>,
+        counters: Rc<Counters>,
+    }
+
+    impl WorkerPool {
+        pub fn spawn(script_url: &str, size: usize) -> Result<Self, PerformError> {
+            let workers = (0..size.max(1))
+                .map(|_| WorkerHandle::spawn(script_url))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Self {
+                workers: Rc::new(workers),
+                counters: Rc::default(),
+            })
+        }
+
+        pub fn install(&self) -> Option<WorkerPool> {
+            INSTALLED.with(|installed| installed.borrow_mut().replace(self.clone()))
+        }
+
+        pub fn installed() -> Option<WorkerPool> {
+            INSTALLED.with(|installed| installed.borrow().clone())
+        }
+
+        pub fn size(&self) -> usize {
+            self.workers.len()
+        }
+
+        pub fn terminate(&self) {
+            self.workers.iter().for_each(WorkerHandle::terminate);
+        }
+
+        pub fn stats(&self) -> PoolStats {
+            let alive = self.workers.iter().filter(|worker| worker.is_alive());
+            PoolStats {
+                workers: self.workers.len(),
+                alive: alive.clone().count(),
+                in_flight: alive.map(WorkerHandle::in_flight).sum(),
+                submitted: self.counters.submitted.get(),
+                completed: self.counters.completed.get(),
+                failed: self.counters.failed.get(),
+            }
+        }
+
+        fn next_worker(&self) -> Option<&WorkerHandle> {
+            let loads = self
+                .workers
+                .iter()
+                .map(|worker| worker.is_alive().then(|| worker.in_flight()))
+                .collect::<Vec<_>>();
+            let index = least_loaded(&loads, self.counters.cursor.get())?;
+            self.counters.cursor.set((index + 1) % loads.len());
+            self.workers.get(index)
+        }
+
+        pub fn run<T: WorkerTask>(
+            &self,
+            task: T,
+        ) -> impl Future<Output = Result<T::Output, PerformError>> + 'static {
+            let counters = self.counters.clone();
+            counters.submitted.set(counters.submitted.get() + 1);
+            let job = self.next_worker().map(|worker| worker.run(task));
+            async move {
+                let result = match job {
+                    Some(job) => job.await,
+                    None => Err(PerformError::Closed),
+                };
+                let counter = if result.is_ok() {
+                    &counters.completed
+                } else {
+                    &counters.failed
+                };
+                counter.set(counter.get() + 1);
+                result
+            }
+        }
+    }
+}
+#[cfg(target_arch = "wasm32")]
+pub use browser::{WorkerHandle, WorkerPool, WorkerServer};
+
+#[cfg(test)]
+mod tests {
+    use super::{least_loaded, WorkerRequest, WorkerResponse, WorkerTask};
+    use crate::{Error, PerformError};
+    use serde::{Deserialize, Serialize};
+
+    mod summed {
+        crate::build_perform!(u32);
+    }
+
+    #[derive(Debug, Error)]
+    #[error("sum overflowed")]
+    struct Overflow;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Sum(Vec<u32>);
+
+    impl WorkerTask for Sum {
+        const NAME: &'static str = "sum";
+        type Output = u32;
+
+        fn run(self) -> Result<u32, PerformError> {
+            self.0
+                .into_iter()
+                .try_fold(0u32, |total, n| total.checked_add(n))
+                .ok_or_else(|| PerformError::task(Overflow))
+        }
+    }
+
+    #[test]
+    fn request_round_trips_and_responds() {
+        let request = WorkerRequest::new(7, Sum(vec![1, 2, 3]));
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(json.contains("\"task\":\"sum\""));
+        assert!(json.contains("\"version\":1"));
+
+        let decoded: WorkerRequest<Sum> = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, request);
+
+        let json = serde_json::to_string(&decoded.respond()).unwrap();
+        let response: WorkerResponse<u32> = serde_json::from_str(&json).unwrap();
+        assert_eq!((response.id, response.task.as_str()), (7, "sum"));
+        assert_eq!(response.result.unwrap(), 6);
+    }
+
+    #[test]
+    fn failures_cross_the_boundary() {
+        let response = WorkerRequest::new(1, Sum(vec![u32::MAX, 1])).respond();
+        let json = serde_json::to_string(&response).unwrap();
+        let response: WorkerResponse<u32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            response.result.unwrap_err().to_string(),
+            "Task failed: sum overflowed"
+        );
+
+        let unknown = WorkerResponse::<()>::unknown_task(2, "resize");
+        let json = serde_json::to_string(&unknown).unwrap();
+        let response: WorkerResponse<u32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            response.result.unwrap_err().to_string(),
+            "Task failed: unknown worker task: resize"
+        );
+    }
+
+    #[test]
+    fn least_loaded_round_robins_ties_and_skips_dead_workers() {
+        let idle = [Some(0), Some(0), Some(0)];
+        assert_eq!(least_loaded(&idle, 0), Some(0));
+        assert_eq!(least_loaded(&idle, 1), Some(1));
+        assert_eq!(least_loaded(&idle, 5), Some(2));
+
+        let busy = [Some(2), None, Some(1), Some(1)];
+        assert_eq!(least_loaded(&busy, 0), Some(2));
+        assert_eq!(least_loaded(&busy, 3), Some(3));
+        assert_eq!(least_loaded(&[None, None], 0), None);
+        assert_eq!(least_loaded(&[], 0), None);
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn blocking_tasks_run_off_the_async_runtime() {
+        use crate::Perform;
+        use std::time::Duration;
+        let session = summed::Session::activate().await;
+        session.perform_blocking_task(Sum(vec![4, 5, 6]));
+        let taken = session.try_take_timeout(Duration::from_secs(5)).await;
+        assert_eq!(taken.unwrap(), 15);
+
+        session.perform_blocking_task(Sum(vec![u32::MAX, 1]));
+        let taken = session.try_take_timeout(Duration::from_secs(5)).await;
+        assert_eq!(
+            taken.unwrap_err().to_string(),
+            "Task failed: sum overflowed"
+        );
+    }
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    #[cfg(target_arch = "wasm32")]
+    async fn blocking_tasks_need_an_installed_pool() {
+        use crate::Perform;
+        let session = summed::Session::activate().await;
+        session.perform_blocking_task(Sum(vec![1]));
+        let taken = crate::tests::polled(|| session.try_take()).await;
+        assert_eq!(
+            taken.unwrap_err().to_string(),
+            "Task failed: no worker pool installed"
+        );
+    }
+}
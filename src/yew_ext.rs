@@ -0,0 +1,105 @@
+use crate::{Perform, PerformError, PollEvent, Session, StoreProvider};
+use futures::channel::mpsc::{self, UnboundedSender};
+use futures::{FutureExt, StreamExt};
+use std::future::Future;
+use std::rc::Rc;
+use yew::{hook, use_force_update, use_memo, use_state, Callback};
+
+#[derive(Debug)]
+pub enum UsePerformState<T> {
+    Idle,
+    Loading,
+    Ready(Rc<T>),
+    Failed(PerformError),
+}
+
+impl<T> Clone for UsePerformState<T> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Idle => Self::Idle,
+            Self::Loading => Self::Loading,
+            Self::Ready(value) => Self::Ready(value.clone()),
+            Self::Failed(error) => Self::Failed(error.clone()),
+        }
+    }
+}
+
+impl<T> UsePerformState<T> {
+    pub fn is_loading(&self) -> bool {
+        matches!(self, Self::Loading)
+    }
+    pub fn value(&self) -> Option<&T> {
+        match self {
+            Self::Ready(value) => Some(value),
+            _ => None,
+        }
+    }
+    pub fn error(&self) -> Option<&PerformError> {
+        match self {
+            Self::Failed(error) => Some(error),
+            _ => None,
+        }
+    }
+    fn settled(result: Result<T, PerformError>) -> Self {
+        match result {
+            Ok(value) => Self::Ready(Rc::new(value)),
+            Err(error) => Self::Failed(error),
+        }
+    }
+}
+
+#[hook]
+pub fn use_perform<P, F, Fut>(factory: F) -> (UsePerformState<P::Value>, Callback<()>)
+where
+    P: StoreProvider,
+    F: Fn() -> Fut + 'static,
+    Fut: Future<Output = P::Value> + 'static,
+{
+    let session = use_memo((), |_| Session::<P>::try_activate());
+    let state = use_state(|| UsePerformState::Idle);
+    let trigger = {
+        let state = state.clone();
+        Callback::from(move |_| {
+            state.set(UsePerformState::Loading);
+            let fut = factory();
+            let session = session.clone();
+            let state = state.clone();
+            crate::spawn_local(async move {
+                session.perform_result(fut.map(Ok)).await;
+                state.set(UsePerformState::settled(session.take_latest().await));
+            });
+        })
+    };
+    ((*state).clone(), trigger)
+}
+
+#[hook]
+pub fn use_poll_adapter() -> UnboundedSender<PollEvent> {
+    let update = use_force_update();
+    let sender = use_memo((), move |_| {
+        let (sender, mut events) = mpsc::unbounded();
+        crate::spawn_local(async move {
+            while events.next().await.is_some() {
+                update.force_update();
+            }
+        });
+        sender
+    });
+    (*sender).clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UsePerformState;
+    use crate::PerformError;
+
+    #[test]
+    fn settled_state_exposes_the_value_or_the_error() {
+        let ready = UsePerformState::settled(Ok(3));
+        assert_eq!(ready.clone().value(), Some(&3));
+        assert!(!ready.is_loading());
+        let failed = UsePerformState::<u32>::settled(Err(PerformError::Cancelled));
+        assert!(matches!(failed.error(), Some(PerformError::Cancelled)));
+        assert_eq!(failed.value(), None);
+    }
+}